@@ -0,0 +1,58 @@
+use crate::{constants::CASE_NAME, fork::Fork};
+
+/// Components of an EF test case name, e.g. `Opcodes_TransactionInit_d111g0v0_Shanghai`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseIndices {
+    /// Name of the test, without the indices and the fork, e.g. `Opcodes_TransactionInit`
+    pub base_name: String,
+    /// Index of the transaction data
+    pub data: u32,
+    /// Index of the transaction gas limit
+    pub gas: u32,
+    /// Index of the transaction value
+    pub value: u32,
+    /// Fork of the case
+    pub fork: Fork,
+}
+
+/// Parses the base name, the `d<N>g<N>v<N>` indices and the fork of a case name.
+/// Returns `None` if the case name doesn't end with the indices and the fork.
+pub fn parse_case_indices(case_name: &str) -> Option<CaseIndices> {
+    let captures = CASE_NAME.captures(case_name)?;
+    Some(CaseIndices {
+        base_name: captures[1].to_string(),
+        data: captures[2].parse().ok()?,
+        gas: captures[3].parse().ok()?,
+        value: captures[4].parse().ok()?,
+        fork: Fork::from(&captures[5]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_case_indices() {
+        // When
+        let indices = parse_case_indices("Opcodes_TransactionInit_d111g0v2_Shanghai");
+
+        // Then
+        assert_eq!(
+            indices,
+            Some(CaseIndices {
+                base_name: "Opcodes_TransactionInit".to_string(),
+                data: 111,
+                gas: 0,
+                value: 2,
+                fork: Fork::Shanghai,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_case_indices_without_indices() {
+        assert_eq!(parse_case_indices("Opcodes_TransactionInit_Shanghai"), None);
+        assert_eq!(parse_case_indices("Opcodes_TransactionInit_d0g0v0"), None);
+    }
+}
@@ -1,6 +1,14 @@
 lazy_static::lazy_static! {
     pub static ref UNSUPPORTED_IDENTIFIER_CHAR: regex::Regex = regex::Regex::new(r"[=^.]").unwrap();
+    pub static ref CASE_INDICES: regex::Regex = regex::Regex::new(r"_d\d+g\d+v\d+$").unwrap();
+    pub static ref CASE_NAME: regex::Regex = regex::Regex::new(r"^(.+)_d(\d+)g(\d+)v(\d+)_([A-Z]\w*)$").unwrap();
+    pub static ref ISSUE_REFERENCE: regex::Regex = regex::Regex::new(r"^(#\d+|https://github\.com/.+/issues/\d+)$").unwrap();
+    pub static ref ENV_PLACEHOLDER: regex::Regex = regex::Regex::new(r"\$\{(\w+)\}").unwrap();
 }
 
 pub const ROOT: &str = "GeneralStateTests";
+pub const BLOCKCHAIN_TESTS: &str = "BlockchainTests";
+pub const STATE_TESTS: &str = "StateTests";
+pub const WILDCARD_FOLDER: &str = "*";
+pub const ALL_FILES: &str = "__all__";
 pub const FORK: &str = "Shanghai";
@@ -97,7 +97,13 @@ impl<'a> EfTests<'a> {
                     }
                     let secret_key = ContentReader::secret_key(file_path.clone())?
                         .ok_or_else(|| eyre::eyre!("Missing secret key"))?;
-                    let is_skipped = self.filter.is_skipped(file_path, Some(case_name.clone()));
+                    // Cases skipped by an entry with the `omit` mode aren't generated
+                    let skip_mode = self.filter.skip_mode(file_path, Some(case_name));
+                    if skip_mode.map_or(false, |mode| mode.is_omit()) {
+                        return Ok(String::new());
+                    }
+                    let is_skipped =
+                        skip_mode.is_some() || !self.filter.is_included(file_path, Some(case_name));
                     Self::format_to_test(case_name, &secret_key, content, is_skipped)
                 })
                 .collect::<Result<Vec<String>, eyre::Error>>()?;
@@ -0,0 +1,127 @@
+use super::{Category, Filter, FilterMap, Folder, SkipEntry};
+
+/// Builder used to construct a [`Filter`] programmatically.
+///
+/// ```
+/// use build_utils::{filter::Filter, path::PathWrapper};
+/// use std::path::PathBuf;
+///
+/// let filter = Filter::builder()
+///     .skip_filename("stRandom", "randomStatetest0")
+///     .skip_regex("stCreate2", "create2collisionStorage.*")
+///     .skip_test("stTransactionTest", "Opcodes_TransactionInit_d0g0v0_Shanghai")
+///     .build();
+///
+/// let path = PathWrapper::from(PathBuf::from("GeneralStateTests/stRandom/randomStatetest0.json"));
+/// assert!(filter.is_skipped(&path, None));
+///
+/// let path = PathWrapper::from(PathBuf::from("GeneralStateTests/stRandom/randomStatetest1.json"));
+/// assert!(!filter.is_skipped(&path, None));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct FilterBuilder {
+    filter: Filter,
+}
+
+impl Filter {
+    /// Returns a builder for an empty filter.
+    pub fn builder() -> FilterBuilder {
+        FilterBuilder::default()
+    }
+}
+
+impl FilterBuilder {
+    /// Skips the file with the given name in the folder.
+    pub fn skip_filename(mut self, folder: impl Into<String>, name: impl Into<String>) -> Self {
+        push_entry(&mut self.filter.filename, folder.into(), name.into());
+        self
+    }
+
+    /// Skips the files matching the regex pattern in the folder.
+    pub fn skip_regex(mut self, folder: impl Into<String>, pattern: impl Into<String>) -> Self {
+        push_entry(&mut self.filter.regex, folder.into(), pattern.into());
+        self
+    }
+
+    /// Skips the test case with the given name in the folder.
+    pub fn skip_test(mut self, folder: impl Into<String>, case: impl Into<String>) -> Self {
+        push_entry(&mut self.filter.test_name, folder.into(), case.into());
+        self
+    }
+
+    /// Builds the filter.
+    pub fn build(self) -> Filter {
+        self.filter
+    }
+}
+
+/// Builds a filter from a stream of rules, each made of the folder, the
+/// mapping and the pattern. Duplicated rules are ignored.
+///
+/// ```
+/// use build_utils::{
+///     filter::{Category, Filter},
+///     path::PathWrapper,
+/// };
+/// use std::path::PathBuf;
+///
+/// let filter: Filter = [
+///     ("stRandom", Category::Filename, "randomStatetest0"),
+///     ("stMemoryStressTest", Category::Regex, "CALL_Bounds.*"),
+/// ]
+/// .into_iter()
+/// .map(|(folder, category, pattern)| (folder.to_string(), category, pattern.to_string()))
+/// .collect();
+///
+/// let path = PathWrapper::from(PathBuf::from("GeneralStateTests/stMemoryStressTest/CALL_Bounds2.json"));
+/// assert!(filter.is_skipped(&path, None));
+/// ```
+impl FromIterator<(Folder, Category, String)> for Filter {
+    fn from_iter<I: IntoIterator<Item = (Folder, Category, String)>>(iter: I) -> Self {
+        let mut filter = Self::default();
+        for (folder, category, pattern) in iter {
+            let map = match category {
+                Category::Filename => &mut filter.filename,
+                Category::Regex => &mut filter.regex,
+                Category::Glob => &mut filter.glob,
+                Category::TestName => &mut filter.test_name,
+                Category::TestNameRegex => &mut filter.test_name_regex,
+                Category::Index => &mut filter.index,
+            };
+            push_entry(map, folder, pattern);
+        }
+        filter
+    }
+}
+
+/// Adds the pattern to the folder's entries, unless already present.
+fn push_entry(map: &mut FilterMap, folder: String, pattern: String) {
+    let entries = map.entry(folder).or_default();
+    if !entries.iter().any(|entry| entry.pattern() == pattern) {
+        entries.push(SkipEntry::from(pattern));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_dedupes() {
+        // Given
+        let filter = Filter::builder()
+            .skip_filename("a", "a")
+            .skip_filename("a", "a")
+            .skip_filename("a", "b")
+            .skip_regex("b", "b.*")
+            .skip_regex("b", "b.*")
+            .skip_test("c", "c")
+            .skip_test("c", "c")
+            .build();
+
+        // Then
+        assert_eq!(filter.filename["a"], vec!["a".into(), "b".into()]);
+        assert_eq!(filter.regex["b"], vec!["b.*".into()]);
+        assert_eq!(filter.test_name["c"], vec!["c".into()]);
+    }
+}
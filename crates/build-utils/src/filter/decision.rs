@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::{walk::test_files, Filter, SkipReason};
+
+/// Skip decision of the filter for a test case, built by [`Filter::decisions`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Decision {
+    /// Path of the test file, relative to the tests root
+    pub path: PathBuf,
+    /// Name of the test case, `None` if the cases of the file couldn't be read
+    pub case: Option<String>,
+    /// Whether the case is skipped or not included by the filter
+    pub skipped: bool,
+    /// The reason for the skip, `None` if the case runs or is only excluded by
+    /// the `only` mapping. Also set for a case running with a `warn` entry.
+    pub reason: Option<SkipReason>,
+}
+
+impl Filter {
+    /// Walks the test files under `test_root` and lazily yields the skip decision
+    /// for each of their cases. Files are only read when the iterator reaches them.
+    pub fn decisions<'a>(&'a self, test_root: &Path) -> impl Iterator<Item = Decision> + 'a {
+        let test_root = test_root.to_path_buf();
+        test_files(&test_root, None).flat_map(move |path| {
+            let file = path
+                .relative_to(&test_root)
+                .map_or_else(|| path.clone().into(), PathBuf::from);
+            let cases = match path.read_case_names() {
+                Ok(cases) => cases.into_iter().map(Some).collect(),
+                Err(_) => vec![None],
+            };
+            cases.into_iter().map(move |case| {
+                let reason = self.skip_reason(&path, case.as_deref());
+                let skipped = reason
+                    .as_ref()
+                    .map_or(false, |reason| reason.severity().is_skip())
+                    || !self.is_included(&path, case.as_deref());
+                Decision {
+                    path: file.clone(),
+                    case,
+                    skipped,
+                    reason,
+                }
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &str = "src/test_data/BlockchainTests/GeneralStateTests";
+
+    #[test]
+    fn test_decisions() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex:
+              stRandom:
+                - randomStatetest0
+            testname: {}
+            ",
+        )
+        .unwrap();
+
+        // When
+        let decisions = filter
+            .decisions(Path::new(TEST_DATA))
+            .filter(|decision| decision.path.starts_with("stRandom"))
+            .map(|decision| serde_json::to_value(decision).unwrap())
+            .collect::<Vec<_>>();
+
+        // Then
+        let skipped = decisions
+            .iter()
+            .find(|decision| decision["path"] == "stRandom/randomStatetest0.json")
+            .unwrap();
+        assert_eq!(
+            skipped,
+            &serde_json::json!({
+                "path": "stRandom/randomStatetest0.json",
+                "case": "randomStatetest0_d0g0v0_Shanghai",
+                "skipped": true,
+                "reason": { "regex": "randomStatetest0" },
+            })
+        );
+        let kept = decisions
+            .iter()
+            .find(|decision| decision["path"] == "stRandom/randomStatetest1.json")
+            .unwrap();
+        assert_eq!(kept["skipped"], false);
+        assert!(kept["reason"].is_null());
+    }
+}
@@ -0,0 +1,838 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use super::{
+    Category, Filter, FilterMap, Folder, FolderConfig, ForkMap, MatchTarget, RegexEngine, SkipEntry,
+};
+use crate::constants::WILDCARD_FOLDER;
+
+/// Folders which differ between two mappings of a filter. For nested mappings,
+/// the differences within a folder present in both mappings are reported as
+/// `/` separated paths, e.g. `stCreate2/Cancun` for the `fork` mapping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FolderDiff {
+    /// Folders only present in the right hand side mapping
+    pub added: Vec<Folder>,
+    /// Folders only present in the left hand side mapping
+    pub removed: Vec<Folder>,
+    /// Folders present in both mappings with different entries
+    pub modified: Vec<Folder>,
+}
+
+impl FolderDiff {
+    /// Returns all the folders which differ
+    pub fn folders(&self) -> Vec<Folder> {
+        self.added
+            .iter()
+            .chain(&self.removed)
+            .chain(&self.modified)
+            .cloned()
+            .collect()
+    }
+
+    /// Returns true if no folder differs
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Folders which differ between two filters, grouped by mapping
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterDiff {
+    /// Differences in the `filename` mapping
+    pub filename: FolderDiff,
+    /// Differences in the `regex` mapping
+    pub regex: FolderDiff,
+    /// Differences in the `testname` mapping
+    pub test_name: FolderDiff,
+    /// Differences in the `testname_regex` mapping
+    pub test_name_regex: FolderDiff,
+    /// Differences in the `glob` mapping
+    pub glob: FolderDiff,
+    /// Differences in the `index` mapping
+    pub index: FolderDiff,
+    /// Differences in the `only` mapping
+    pub only: FolderDiff,
+    /// Differences in the `keep` mapping
+    pub keep: FolderDiff,
+    /// Differences in the `extension` mapping
+    pub extension: FolderDiff,
+    /// Differences in the `hash` mapping
+    pub hash: FolderDiff,
+    /// Differences in the `fork` mapping, as `folder/fork` paths for the folders
+    /// present in both filters
+    pub fork: FolderDiff,
+    /// Differences in the settings of the folders, e.g. their default severity
+    pub folders: FolderDiff,
+    /// Patterns removed from a mapping and added to another one for the same folder.
+    /// The folders involved are still reported in the per mapping differences.
+    pub moved: Vec<Moved>,
+}
+
+/// Pattern moved between two mappings of the same folder, e.g. from `filename`
+/// to `regex`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Moved {
+    /// Folder containing the pattern
+    pub folder: Folder,
+    /// The moved pattern
+    pub pattern: String,
+    /// Mapping containing the pattern in the left hand side filter
+    pub from: Category,
+    /// Mapping containing the pattern in the right hand side filter
+    pub to: Category,
+}
+
+impl FilterDiff {
+    /// Returns all the folders which differ, without duplicates
+    pub fn folders(&self) -> Vec<Folder> {
+        let mut folders: Vec<Folder> = Vec::new();
+        for diff in [
+            &self.filename,
+            &self.regex,
+            &self.test_name,
+            &self.test_name_regex,
+            &self.glob,
+            &self.index,
+            &self.only,
+            &self.keep,
+            &self.extension,
+            &self.hash,
+            &self.fork,
+            &self.folders,
+        ] {
+            for folder in diff.folders() {
+                if !folders.contains(&folder) {
+                    folders.push(folder);
+                }
+            }
+        }
+        folders
+    }
+}
+
+/// Patterns which differ between two filters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DiffReport {
+    /// Folders and patterns only present in the right hand side filter
+    pub added: Vec<(Folder, String)>,
+    /// Folders and patterns only present in the left hand side filter
+    pub removed: Vec<(Folder, String)>,
+}
+
+/// Number of patterns added and removed between two filters, built by
+/// [`Filter::change_summary`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChangeSummary {
+    /// Number of patterns only present in the right hand side filter
+    pub added: usize,
+    /// Number of patterns only present in the left hand side filter
+    pub removed: usize,
+    /// Number of added and removed patterns for each mapping with changes. The
+    /// `fork` mapping, which has no category, is only counted in the totals.
+    pub per_category: BTreeMap<Category, (usize, usize)>,
+}
+
+/// Relation between the skip patterns of two filters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterRelation {
+    /// The left hand side filter skips a strict subset of the patterns of the right one
+    StrictlyMorePermissive,
+    /// The left hand side filter skips a strict superset of the patterns of the right one
+    StrictlyMoreRestrictive,
+    /// Both filters skip the same patterns
+    Equivalent,
+    /// Each filter skips patterns the other doesn't
+    Incomparable,
+}
+
+impl Filter {
+    /// Returns the difference in keys (folders) between the two filters. Unlike
+    /// [`Filter::detailed_diff`], the `fork` mapping is only compared per folder, so
+    /// that every returned key is a folder. Returns `None` if the change may affect
+    /// every folder: when a setting changing which tests are skipped differs, such
+    /// as `skip_forks` or `case_insensitive`, or when the `*` folder differs.
+    pub fn diff(&self, rhs: &Self) -> Option<Vec<Folder>> {
+        if self.global_settings() != rhs.global_settings() {
+            return None;
+        }
+        let (lhs, rhs) = (self.without_reasons(), rhs.without_reasons());
+        let mut diff = lhs.entries_diff(&rhs);
+        diff.fork = map_diff(&flatten_fork_map(&lhs.fork), &flatten_fork_map(&rhs.fork));
+        let folders = diff.folders();
+        (!folders.iter().any(|folder| folder == WILDCARD_FOLDER)).then_some(folders)
+    }
+
+    /// Returns the settings changing which tests of every folder are skipped.
+    fn global_settings(
+        &self,
+    ) -> (
+        BTreeSet<&str>,
+        bool,
+        bool,
+        MatchTarget,
+        bool,
+        bool,
+        RegexEngine,
+    ) {
+        (
+            self.skip_forks.iter().map(String::as_str).collect(),
+            self.case_insensitive,
+            self.is_full_match(),
+            self.match_target,
+            self.cascade,
+            self.path_scoped,
+            self.regex_engine,
+        )
+    }
+
+    /// Returns the difference in keys (folders) between the two filters, grouped
+    /// by mapping. Added folders are the ones only present in `rhs`, removed
+    /// folders the ones only present in `self`. The differences in the `fork`
+    /// mapping are reported down to the changed fork of a folder. Entries differing
+    /// only in their [reasons](Filter::without_reasons) are considered equal.
+    /// Patterns moving between the `filename`, `regex`, `glob`, `testname`,
+    /// `testname_regex` and `index` mappings of a folder are also reported in
+    /// [`FilterDiff::moved`].
+    pub fn detailed_diff(&self, rhs: &Self) -> FilterDiff {
+        let mut diff = self.without_reasons().entries_diff(&rhs.without_reasons());
+        diff.moved = self.moved_patterns(rhs);
+        diff
+    }
+
+    /// Returns the patterns removed from a category of a folder and added to another
+    /// category of the same folder.
+    fn moved_patterns(&self, rhs: &Self) -> Vec<Moved> {
+        let changes = |category: Category| {
+            let (lhs, rhs) = (self.category_map(category), rhs.category_map(category));
+            (
+                missing_patterns(lhs, rhs).collect::<Vec<_>>(),
+                missing_patterns(rhs, lhs).collect::<Vec<_>>(),
+            )
+        };
+        let changes = Category::ALL.map(|category| (category, changes(category)));
+
+        let mut moved = Vec::new();
+        for (from, (removed, _)) in &changes {
+            for (folder, pattern) in removed {
+                let to = changes.iter().find(|(to, (_, added))| {
+                    to != from && added.iter().any(|(f, p)| f == folder && p == pattern)
+                });
+                if let Some((to, _)) = to {
+                    moved.push(Moved {
+                        folder: folder.clone(),
+                        pattern: pattern.clone(),
+                        from: *from,
+                        to: *to,
+                    });
+                }
+            }
+        }
+        moved
+    }
+
+    /// Returns the difference in keys (folders) between the entries of the two
+    /// filters, grouped by mapping.
+    fn entries_diff(&self, rhs: &Self) -> FilterDiff {
+        FilterDiff {
+            filename: map_diff(&self.filename, &rhs.filename),
+            regex: map_diff(&self.regex, &rhs.regex),
+            test_name: map_diff(&self.test_name, &rhs.test_name),
+            test_name_regex: map_diff(&self.test_name_regex, &rhs.test_name_regex),
+            glob: map_diff(&self.glob, &rhs.glob),
+            index: map_diff(&self.index, &rhs.index),
+            only: map_diff(&self.only, &rhs.only),
+            keep: map_diff(&self.keep, &rhs.keep),
+            extension: map_diff(&self.extension, &rhs.extension),
+            hash: map_diff(&self.hash, &rhs.hash),
+            fork: map_diff(&self.fork, &rhs.fork),
+            folders: map_diff(&self.folders, &rhs.folders),
+            moved: Vec::new(),
+        }
+    }
+
+    /// Returns the relation of the filter to `rhs`, by comparing their sets of
+    /// patterns per folder and mapping. Patterns are compared syntactically.
+    pub fn relation(&self, rhs: &Self) -> FilterRelation {
+        let report = self.diff_report(rhs);
+        match (report.added.is_empty(), report.removed.is_empty()) {
+            (true, true) => FilterRelation::Equivalent,
+            (false, true) => FilterRelation::StrictlyMorePermissive,
+            (true, false) => FilterRelation::StrictlyMoreRestrictive,
+            (false, false) => FilterRelation::Incomparable,
+        }
+    }
+
+    /// Returns the number of skip patterns added and removed between the two filters,
+    /// in total and per mapping. Patterns are compared as in [`Filter::diff_report`].
+    pub fn change_summary(&self, rhs: &Self) -> ChangeSummary {
+        let mut summary = ChangeSummary::default();
+        for category in Category::ALL {
+            let (lhs, rhs) = (self.category_map(category), rhs.category_map(category));
+            let added = missing_patterns(rhs, lhs).count();
+            let removed = missing_patterns(lhs, rhs).count();
+            if added > 0 || removed > 0 {
+                summary.per_category.insert(category, (added, removed));
+            }
+            summary.added += added;
+            summary.removed += removed;
+        }
+
+        let (lhs_forks, rhs_forks) = (flatten_fork_map(&self.fork), flatten_fork_map(&rhs.fork));
+        summary.added += missing_patterns(&rhs_forks, &lhs_forks).count();
+        summary.removed += missing_patterns(&lhs_forks, &rhs_forks).count();
+        summary
+    }
+
+    /// Returns the skip patterns added and removed between the two filters. The
+    /// entries of the `fork` mapping are reported as `fork/test` patterns. Added
+    /// patterns are the ones only present in `rhs`, removed patterns the ones only
+    /// present in `self`.
+    pub fn diff_report(&self, rhs: &Self) -> DiffReport {
+        let mut report = DiffReport::default();
+        let lhs_forks = flatten_fork_map(&self.fork);
+        let rhs_forks = flatten_fork_map(&rhs.fork);
+        let maps = Category::ALL
+            .into_iter()
+            .map(|category| (self.category_map(category), rhs.category_map(category)))
+            .chain([(&lhs_forks, &rhs_forks)]);
+        for (lhs, rhs) in maps {
+            report.added.extend(missing_patterns(rhs, lhs));
+            report.removed.extend(missing_patterns(lhs, rhs));
+        }
+        report
+    }
+}
+
+/// Returns the folders and patterns of `lhs` which are absent from `rhs`.
+fn missing_patterns<'a>(
+    lhs: &'a FilterMap,
+    rhs: &'a FilterMap,
+) -> impl Iterator<Item = (Folder, String)> + 'a {
+    lhs.iter().flat_map(move |(folder, entries)| {
+        let rhs_entries = rhs.get(folder);
+        entries
+            .iter()
+            .filter(move |entry| {
+                rhs_entries.map_or(true, |rhs_entries| {
+                    !rhs_entries.iter().any(|e| e.pattern() == entry.pattern())
+                })
+            })
+            .map(move |entry| (folder.clone(), entry.pattern().to_string()))
+    })
+}
+
+/// Flattens the fork mapping into a mapping from directories to `fork/test` entries.
+pub(super) fn flatten_fork_map(forks: &ForkMap) -> FilterMap {
+    forks
+        .iter()
+        .map(|(folder, forks)| {
+            let entries = forks
+                .iter()
+                .flat_map(|(fork, tests)| {
+                    tests
+                        .iter()
+                        .map(move |test| test.with_pattern(format!("{fork}/{}", test.pattern())))
+                })
+                .collect();
+            (folder.clone(), entries)
+        })
+        .collect()
+}
+
+/// Value of a mapping of the filter which can be compared key by key
+trait NestedDiff {
+    /// Records the differences between the two values found at `path`.
+    fn diff_into(path: &str, lhs: &Self, rhs: &Self, diff: &mut FolderDiff);
+}
+
+impl NestedDiff for Vec<SkipEntry> {
+    fn diff_into(path: &str, lhs: &Self, rhs: &Self, diff: &mut FolderDiff) {
+        // The order of the entries is irrelevant, compare them as sets
+        if lhs.iter().collect::<BTreeSet<_>>() != rhs.iter().collect::<BTreeSet<_>>() {
+            diff.modified.push(path.to_string());
+        }
+    }
+}
+
+impl NestedDiff for FolderConfig {
+    fn diff_into(path: &str, lhs: &Self, rhs: &Self, diff: &mut FolderDiff) {
+        if lhs != rhs {
+            diff.modified.push(path.to_string());
+        }
+    }
+}
+
+impl<V: NestedDiff> NestedDiff for BTreeMap<String, V> {
+    fn diff_into(path: &str, lhs: &Self, rhs: &Self, diff: &mut FolderDiff) {
+        let key_path = |key: &str| {
+            if path.is_empty() {
+                key.to_string()
+            } else {
+                format!("{path}/{key}")
+            }
+        };
+        for (key, lhs_value) in lhs {
+            match rhs.get(key) {
+                Some(rhs_value) => V::diff_into(&key_path(key), lhs_value, rhs_value, diff),
+                None => diff.removed.push(key_path(key)),
+            }
+        }
+        for key in rhs.keys() {
+            if !lhs.contains_key(key) {
+                diff.added.push(key_path(key));
+            }
+        }
+    }
+}
+
+fn map_diff<V: NestedDiff>(lhs: &BTreeMap<Folder, V>, rhs: &BTreeMap<Folder, V>) -> FolderDiff {
+    let mut diff = FolderDiff::default();
+    NestedDiff::diff_into("", lhs, rhs, &mut diff);
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_diff() {
+        // Given
+        let lhs: FilterMap = vec![
+            ("a".to_string(), vec!["a".into()]),
+            ("b".to_string(), vec!["b".into(), "b".into()]),
+            ("c".to_string(), vec!["c".into(), "c".into(), "c".into()]),
+            ("e".to_string(), vec!["e".into(), "f".into(), "g".into()]),
+            ("f".to_string(), vec!["a".into(), "b".into()]),
+        ]
+        .into_iter()
+        .collect();
+        let rhs: FilterMap = vec![
+            ("a".to_string(), vec!["a".into()]),
+            ("b".to_string(), vec!["b".into(), "d".into()]),
+            ("c".to_string(), vec!["c".into(), "c".into(), "c".into()]),
+            ("d".to_string(), vec!["e".into(), "f".into(), "g".into()]),
+            ("f".to_string(), vec!["b".into(), "a".into()]),
+        ]
+        .into_iter()
+        .collect();
+
+        // When
+        let mut diff = map_diff(&lhs, &rhs).folders();
+        diff.sort();
+
+        // Then
+        let expected: Vec<Folder> = vec!["b".to_string(), "d".to_string(), "e".to_string()]
+            .into_iter()
+            .collect();
+
+        assert_eq!(diff, expected)
+    }
+
+    #[test]
+    fn test_map_diff_ignores_order() {
+        // Given
+        let lhs: FilterMap = vec![("a".to_string(), vec!["a".into(), "b".into()])]
+            .into_iter()
+            .collect();
+        let rhs: FilterMap = vec![("a".to_string(), vec!["b".into(), "a".into()])]
+            .into_iter()
+            .collect();
+
+        // When
+        let diff = map_diff(&lhs, &rhs);
+
+        // Then
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_report() {
+        // Given
+        let lhs: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom: [randomStatetest0, randomStatetest1]
+              stDeleted: [deletedTest]
+            regex: {}
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let rhs: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom: [randomStatetest1, randomStatetest2]
+            regex:
+              stBadOpcode: [opc.*]
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let report = lhs.diff_report(&rhs);
+
+        // Then
+        let pair = |folder: &str, pattern: &str| (folder.to_string(), pattern.to_string());
+        assert_eq!(
+            report,
+            DiffReport {
+                added: vec![
+                    pair("stRandom", "randomStatetest2"),
+                    pair("stBadOpcode", "opc.*"),
+                    pair("stTransactionTest", "Cancun/Opcodes_TransactionInit"),
+                ],
+                removed: vec![
+                    pair("stDeleted", "deletedTest"),
+                    pair("stRandom", "randomStatetest0"),
+                ],
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&report.removed).unwrap(),
+            r#"[["stDeleted","deletedTest"],["stRandom","randomStatetest0"]]"#
+        );
+    }
+
+    #[test]
+    fn test_change_summary() {
+        // Given
+        let lhs: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom: [randomStatetest0, randomStatetest1]
+              stDeleted: [deletedTest]
+            regex:
+              stBadOpcode: [opc.*]
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let rhs: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom: [randomStatetest1, randomStatetest2, randomStatetest3]
+            regex:
+              stBadOpcode: [opc.*]
+            testname:
+              stTransactionTest: [Opcodes_TransactionInit_d0g0v0_Shanghai]
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let summary = lhs.change_summary(&rhs);
+
+        // Then
+        assert_eq!(
+            summary,
+            ChangeSummary {
+                added: 4,
+                removed: 2,
+                per_category: BTreeMap::from([
+                    (Category::Filename, (2, 2)),
+                    (Category::TestName, (1, 0)),
+                ]),
+            }
+        );
+        assert_eq!(rhs.change_summary(&rhs), ChangeSummary::default());
+    }
+
+    #[test]
+    fn test_relation() {
+        // Given
+        let filter = |filename: &str| -> Filter {
+            serde_yaml::from_str(&format!(
+                "filename: {filename}\nregex: {{}}\ntestname: {{}}"
+            ))
+            .unwrap()
+        };
+        let old = filter("{stRandom: [randomStatetest0, randomStatetest1]}");
+        let fixed = filter("{stRandom: [randomStatetest0]}");
+        let regressed =
+            filter("{stRandom: [randomStatetest0, randomStatetest1, randomStatetest2]}");
+        let reordered = filter("{stRandom: [randomStatetest1, randomStatetest0]}");
+        let changed = filter("{stRandom: [randomStatetest0, randomStatetest2]}");
+
+        // Then
+        assert_eq!(fixed.relation(&old), FilterRelation::StrictlyMorePermissive);
+        assert_eq!(
+            regressed.relation(&old),
+            FilterRelation::StrictlyMoreRestrictive
+        );
+        assert_eq!(reordered.relation(&old), FilterRelation::Equivalent);
+        assert_eq!(changed.relation(&old), FilterRelation::Incomparable);
+    }
+
+    #[test]
+    fn test_detailed_diff() {
+        // Given
+        let lhs: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              a: [a]
+              b: [b, b]
+              e: [e, f, g]
+            regex:
+              c: [c.*]
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let rhs: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              a: [a]
+              b: [b, d]
+              d: [e, f, g]
+            regex:
+              c: [c.*]
+              f: [f.*]
+            testname: {}
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let diff = lhs.detailed_diff(&rhs);
+
+        // Then
+        assert_eq!(
+            diff.filename,
+            FolderDiff {
+                added: vec!["d".to_string()],
+                removed: vec!["e".to_string()],
+                modified: vec!["b".to_string()],
+            }
+        );
+        assert_eq!(
+            diff.regex,
+            FolderDiff {
+                added: vec!["f".to_string()],
+                ..Default::default()
+            }
+        );
+        assert!(diff.test_name.is_empty());
+        assert!(diff.test_name_regex.is_empty());
+        assert!(diff.glob.is_empty());
+        assert!(diff.only.is_empty());
+        assert!(diff.keep.is_empty());
+        assert!(diff.fork.is_empty());
+
+        let mut folders = lhs.diff(&rhs).unwrap();
+        folders.sort();
+        assert_eq!(folders, vec!["b", "d", "e", "f"]);
+    }
+
+    #[test]
+    fn test_diff_settings() {
+        // Given
+        let lhs: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom: [randomStatetest0]
+              stBadOpcode: [opc4DDiffPlaces]
+            regex: {}
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let default_severity: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom: [randomStatetest0]
+              stBadOpcode: [opc4DDiffPlaces]
+            regex: {}
+            testname: {}
+            folders:
+              stRandom:
+                default_severity: warn
+            ",
+        )
+        .unwrap();
+        let case_insensitive = Filter {
+            case_insensitive: true,
+            ..lhs.clone()
+        };
+        let skip_forks = Filter {
+            skip_forks: vec!["cancun".to_string()],
+            ..lhs.clone()
+        };
+
+        // Then
+        assert_eq!(
+            lhs.diff(&default_severity),
+            Some(vec!["stRandom".to_string()])
+        );
+        assert_eq!(
+            lhs.detailed_diff(&default_severity).folders.added,
+            vec!["stRandom".to_string()]
+        );
+        assert_eq!(lhs.diff(&case_insensitive), None);
+        assert_eq!(lhs.diff(&skip_forks), None);
+        assert_eq!(lhs.diff(&lhs.clone()), Some(vec![]));
+    }
+
+    #[test]
+    fn test_detailed_diff_moved() {
+        // Given
+        let lhs: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom: [randomStatetest0, randomStatetest1]
+            regex: {}
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let rhs: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom: [randomStatetest1]
+            regex:
+              stRandom: [randomStatetest0]
+              stBadOpcode: [randomStatetest1]
+            testname: {}
+            ",
+        )
+        .unwrap();
+
+        // When
+        let diff = lhs.detailed_diff(&rhs);
+
+        // Then
+        assert_eq!(
+            diff.moved,
+            vec![Moved {
+                folder: "stRandom".to_string(),
+                pattern: "randomStatetest0".to_string(),
+                from: Category::Filename,
+                to: Category::Regex,
+            }]
+        );
+        assert_eq!(diff.filename.modified, vec!["stRandom".to_string()]);
+    }
+
+    #[test]
+    fn test_diff_ignores_reasons() {
+        // Given
+        let lhs: Filter = serde_yaml::from_str(
+            r##"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - pattern: randomStatetest1
+                  reason: flaky
+            regex: {}
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+            "##,
+        )
+        .unwrap();
+        let rhs: Filter = serde_yaml::from_str(
+            r##"
+            filename:
+              stRandom:
+                - pattern: randomStatetest0
+                  reason: gas accounting
+                  issue: "#123"
+                - pattern: randomStatetest1
+                  expires: 2024-06-30
+            regex: {}
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun:
+                  - pattern: Opcodes_TransactionInit
+                    reason: not implemented
+            "##,
+        )
+        .unwrap();
+        let warn: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - pattern: randomStatetest1
+                  severity: warn
+            regex: {}
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+            "#,
+        )
+        .unwrap();
+
+        // Then
+        assert_ne!(lhs, rhs);
+        assert_eq!(lhs.without_reasons(), rhs.without_reasons());
+        assert_eq!(lhs.diff(&rhs), Some(vec![]));
+        assert_eq!(lhs.detailed_diff(&rhs), FilterDiff::default());
+        assert_eq!(lhs.relation(&rhs), FilterRelation::Equivalent);
+        assert_eq!(lhs.diff(&warn), Some(vec!["stRandom".to_string()]));
+    }
+
+    #[test]
+    fn test_detailed_diff_nested_fork() {
+        // Given
+        let lhs: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex: {}
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+                Shanghai: [Opcodes_TransactionInit]
+              stRandom:
+                Cancun: [randomStatetest0]
+            "#,
+        )
+        .unwrap();
+        let rhs: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex: {}
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+                Shanghai: [Opcodes_TransactionInit, Opcodes_TransactionDeleted]
+              stRandom:
+                Cancun: [randomStatetest0]
+                Prague: [randomStatetest0]
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let diff = lhs.detailed_diff(&rhs);
+
+        // Then
+        assert_eq!(
+            diff.fork,
+            FolderDiff {
+                added: vec!["stRandom/Prague".to_string()],
+                removed: vec![],
+                modified: vec!["stTransactionTest/Shanghai".to_string()],
+            }
+        );
+        assert!(diff.filename.is_empty());
+
+        let mut folders = lhs.diff(&rhs).unwrap();
+        folders.sort();
+        assert_eq!(folders, vec!["stRandom", "stTransactionTest"]);
+    }
+}
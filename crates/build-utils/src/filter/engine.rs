@@ -0,0 +1,41 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Engine compiling the regex patterns of the filter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegexEngine {
+    /// The `regex` crate, which guarantees linear time matching
+    #[default]
+    Default,
+    /// The `fancy-regex` crate, which supports lookarounds and backreferences.
+    /// Requires the `fancy-regex` feature.
+    Fancy,
+}
+
+impl RegexEngine {
+    /// Returns true for the default engine
+    pub fn is_default(&self) -> bool {
+        *self == Self::Default
+    }
+}
+
+/// Regex pattern compiled by one of the [engines](RegexEngine)
+#[derive(Debug, Clone)]
+pub(super) enum CompiledRegex {
+    Default(Regex),
+    #[cfg(feature = "fancy-regex")]
+    Fancy(Box<fancy_regex::Regex>),
+}
+
+impl CompiledRegex {
+    /// Checks if the regex matches the text. With the fancy engine, a match which
+    /// errors, e.g. by exceeding the backtracking limit, counts as no match.
+    pub(super) fn is_match(&self, text: &str) -> bool {
+        match self {
+            Self::Default(regex) => regex.is_match(text),
+            #[cfg(feature = "fancy-regex")]
+            Self::Fancy(regex) => regex.is_match(text).unwrap_or_default(),
+        }
+    }
+}
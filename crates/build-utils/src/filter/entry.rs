@@ -0,0 +1,222 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::Category;
+
+/// Entry of a filter mapping. Entries are either a bare pattern or
+/// an object containing the pattern along with some metadata.
+///
+/// # Example
+///
+/// ```yaml
+/// stBadOpcode:
+///   - ^opc.*
+///   - pattern: invalidDiffPlaces
+///     reason: "flaky gas accounting #123"
+///     expires: 2024-06-30
+///     issue: "#123"
+///     severity: warn
+///   - pattern: opcA5DiffPlaces
+///     mode: ignore
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum SkipEntry {
+    /// Bare pattern
+    Pattern(String),
+    /// Pattern with its metadata
+    Detailed {
+        /// The pattern to match
+        pattern: String,
+        /// Human-readable reason for the skip
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        /// Date after which the skip should be reviewed
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires: Option<NaiveDate>,
+        /// Reference to the issue tracking the skip, e.g. `#123`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        issue: Option<String>,
+        /// Whether the matching tests are skipped or only reported as warnings
+        #[serde(default, skip_serializing_if = "Severity::is_skip")]
+        severity: Severity,
+        /// Whether the skipped tests are omitted or generated as ignored tests
+        #[serde(default, skip_serializing_if = "SkipMode::is_omit")]
+        mode: SkipMode,
+    },
+}
+
+/// Severity of a filter entry
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The matching tests don't run
+    #[default]
+    Skip,
+    /// The matching tests run, but their failures are only reported as warnings
+    Warn,
+}
+
+impl Severity {
+    /// Returns true for the default severity, skipping the tests
+    pub fn is_skip(&self) -> bool {
+        *self == Self::Skip
+    }
+}
+
+/// How the tests skipped by a filter entry are handled by the test generation.
+/// Tests excluded by the `only` mapping are always generated as ignored tests.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SkipMode {
+    /// The tests aren't generated
+    #[default]
+    Omit,
+    /// The tests are generated with an `#[ignore]` attribute, so that they still
+    /// run with `cargo test -- --ignored`
+    Ignore,
+}
+
+impl SkipMode {
+    /// Returns true for the default mode, omitting the tests
+    pub fn is_omit(&self) -> bool {
+        *self == Self::Omit
+    }
+}
+
+impl SkipEntry {
+    /// Returns the pattern of the entry
+    pub fn pattern(&self) -> &str {
+        match self {
+            Self::Pattern(pattern) | Self::Detailed { pattern, .. } => pattern,
+        }
+    }
+
+    /// Returns the reason of the entry, if any
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            Self::Pattern(_) => None,
+            Self::Detailed { reason, .. } => reason.as_deref(),
+        }
+    }
+
+    /// Returns the expiry date of the entry, if any
+    pub fn expires(&self) -> Option<NaiveDate> {
+        match self {
+            Self::Pattern(_) => None,
+            Self::Detailed { expires, .. } => *expires,
+        }
+    }
+
+    /// Returns the issue reference of the entry, if any
+    pub fn issue(&self) -> Option<&str> {
+        match self {
+            Self::Pattern(_) => None,
+            Self::Detailed { issue, .. } => issue.as_deref(),
+        }
+    }
+
+    /// Returns the severity of the entry, [`Severity::Skip`] for a bare pattern
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Pattern(_) => Severity::Skip,
+            Self::Detailed { severity, .. } => *severity,
+        }
+    }
+
+    /// Returns the skip mode of the entry, [`SkipMode::Omit`] for a bare pattern
+    pub fn mode(&self) -> SkipMode {
+        match self {
+            Self::Pattern(_) => SkipMode::Omit,
+            Self::Detailed { mode, .. } => *mode,
+        }
+    }
+
+    /// Returns a copy of the entry without its reason, expiry date and issue
+    /// reference. Entries left without metadata become bare patterns.
+    pub fn without_reasons(&self) -> Self {
+        let (severity, mode) = (self.severity(), self.mode());
+        if severity.is_skip() && mode.is_omit() {
+            return Self::Pattern(self.pattern().to_string());
+        }
+        Self::Detailed {
+            pattern: self.pattern().to_string(),
+            reason: None,
+            expires: None,
+            issue: None,
+            severity,
+            mode,
+        }
+    }
+
+    /// Returns a copy of the entry with the pattern replaced
+    pub(crate) fn with_pattern(&self, pattern: String) -> Self {
+        match self {
+            Self::Pattern(_) => Self::Pattern(pattern),
+            Self::Detailed {
+                reason,
+                expires,
+                issue,
+                severity,
+                mode,
+                ..
+            } => Self::Detailed {
+                pattern,
+                reason: reason.clone(),
+                expires: *expires,
+                issue: issue.clone(),
+                severity: *severity,
+                mode: *mode,
+            },
+        }
+    }
+}
+
+impl From<&str> for SkipEntry {
+    fn from(pattern: &str) -> Self {
+        Self::Pattern(pattern.to_string())
+    }
+}
+
+impl From<String> for SkipEntry {
+    fn from(pattern: String) -> Self {
+        Self::Pattern(pattern)
+    }
+}
+
+/// Borrowed view over an entry of a filter, along with its location
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterEntryRef<'a> {
+    /// The folder containing the entry
+    pub folder: &'a str,
+    /// The mapping containing the entry
+    pub category: Category,
+    /// The pattern of the entry
+    pub pattern: &'a str,
+    /// The reason of the entry, if any
+    pub reason: Option<&'a str>,
+    /// The expiry date of the entry, if any
+    pub expires: Option<NaiveDate>,
+}
+
+/// Entry of any mapping whose expiry date has passed, returned by
+/// [`Filter::expired`](super::Filter::expired)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpiredEntry<'a> {
+    /// The folder containing the entry
+    pub folder: &'a str,
+    /// The name of the mapping containing the entry, e.g. `filename` or `fork`
+    pub map: &'static str,
+    /// The fork listing the entry, for an entry of the `fork` mapping
+    pub fork: Option<&'a str>,
+    /// The pattern of the entry
+    pub pattern: &'a str,
+    /// The reason of the entry, if any
+    pub reason: Option<&'a str>,
+    /// The expiry date of the entry
+    pub expires: NaiveDate,
+}
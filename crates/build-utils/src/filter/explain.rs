@@ -0,0 +1,114 @@
+use super::{Category, Filter, FilterEntryRef, SkipReason};
+use crate::path::PathWrapper;
+
+/// Explanation of the skip decision for a test, built by [`Filter::explain`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Explanation<'a> {
+    /// The test matches an entry of the filter
+    Skipped(SkipReason),
+    /// The test is listed in the `keep` mapping
+    Kept,
+    /// The test doesn't match any entry. Contains the entries applying to the
+    /// directory of the test which were checked.
+    NotSkipped(Vec<FilterEntryRef<'a>>),
+}
+
+impl Filter {
+    /// Explains the skip decision for the given path: either the entry it matches, or
+    /// the entries of the `filename`, `regex`, `glob`, `testname`, `testname_regex`
+    /// and `index` mappings which apply to its directory but didn't match.
+    pub fn explain(&self, path: &PathWrapper, case_name: Option<&str>) -> Explanation<'_> {
+        if let Some(reason) = self.skip_reason(path, case_name) {
+            return Explanation::Skipped(reason);
+        }
+
+        let dir_names = self.folder_keys(path);
+        let file_name = self.match_name(path);
+        if dir_names
+            .iter()
+            .any(|dir_name| self.is_kept(dir_name, &file_name, case_name))
+        {
+            return Explanation::Kept;
+        }
+
+        let mut candidates = Vec::new();
+        for category in Category::ALL {
+            let mut folders = Vec::new();
+            for dir_name in &dir_names {
+                for (folder, entries) in
+                    self.matching_folders(self.category_map(category), dir_name)
+                {
+                    // With `cascade`, the `*` folder applies to each of the directories
+                    if folders.contains(&folder) {
+                        continue;
+                    }
+                    folders.push(folder);
+                    candidates.extend(entries.iter().map(|entry| FilterEntryRef {
+                        folder,
+                        category,
+                        pattern: entry.pattern(),
+                        reason: entry.reason(),
+                        expires: entry.expires(),
+                    }));
+                }
+            }
+        }
+        Explanation::NotSkipped(candidates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_explain() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+              stBadOpcode:
+                - opc4DDiffPlaces
+            regex:
+              '*':
+                - .*_benchmark
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let path =
+            |name: &str| PathWrapper::from(PathBuf::from("GeneralStateTests/stRandom").join(name));
+
+        // When
+        let skipped = filter.explain(&path("randomStatetest0.json"), None);
+        let not_skipped = filter.explain(&path("randomStatetest1.json"), None);
+
+        // Then
+        assert_eq!(
+            skipped,
+            Explanation::Skipped(SkipReason::Filename("randomStatetest0".into()))
+        );
+        assert_eq!(
+            not_skipped,
+            Explanation::NotSkipped(vec![
+                FilterEntryRef {
+                    folder: "stRandom",
+                    category: Category::Filename,
+                    pattern: "randomStatetest0",
+                    reason: None,
+                    expires: None,
+                },
+                FilterEntryRef {
+                    folder: "*",
+                    category: Category::Regex,
+                    pattern: ".*_benchmark",
+                    reason: None,
+                    expires: None,
+                },
+            ])
+        );
+    }
+}
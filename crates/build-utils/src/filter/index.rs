@@ -0,0 +1,89 @@
+use std::ops::RangeInclusive;
+
+use crate::case::CaseIndices;
+
+/// Predicate on the indices of a case name, parsed from an entry of the `index`
+/// mapping. The entry is a sequence of `d`, `g` or `v` components, each followed
+/// by an index or an inclusive range of indices, e.g. `v0` or `d1-3v0`. A case
+/// matches if all the components match.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct IndexPredicate {
+    data: Option<RangeInclusive<u32>>,
+    gas: Option<RangeInclusive<u32>>,
+    value: Option<RangeInclusive<u32>>,
+}
+
+impl IndexPredicate {
+    /// Parses the predicate, returning `None` if the pattern is invalid.
+    pub(super) fn parse(pattern: &str) -> Option<Self> {
+        let mut predicate = Self::default();
+        let mut rest = pattern;
+        if rest.is_empty() {
+            return None;
+        }
+        while !rest.is_empty() {
+            let mut chars = rest.chars();
+            let component = chars.next()?;
+            rest = chars.as_str();
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit() && c != '-')
+                .unwrap_or(rest.len());
+            let (range, tail) = rest.split_at(end);
+            rest = tail;
+
+            let range = match range.split_once('-') {
+                Some((start, end)) => start.parse().ok()?..=end.parse().ok()?,
+                None => {
+                    let index = range.parse().ok()?;
+                    index..=index
+                }
+            };
+            let slot = match component {
+                'd' => &mut predicate.data,
+                'g' => &mut predicate.gas,
+                'v' => &mut predicate.value,
+                _ => return None,
+            };
+            if slot.replace(range).is_some() {
+                return None;
+            }
+        }
+        Some(predicate)
+    }
+
+    /// Checks if the indices of a case match the predicate.
+    pub(super) fn matches(&self, indices: &CaseIndices) -> bool {
+        let matches = |range: &Option<RangeInclusive<u32>>, index| {
+            range.as_ref().map_or(true, |range| range.contains(&index))
+        };
+        matches(&self.data, indices.data)
+            && matches(&self.gas, indices.gas)
+            && matches(&self.value, indices.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::case::parse_case_indices;
+
+    #[test]
+    fn test_index_predicate() {
+        // Given
+        let case = |name: &str| parse_case_indices(name).unwrap();
+        let predicate = IndexPredicate::parse("d1-3v0").unwrap();
+
+        // Then
+        assert!(predicate.matches(&case("Opcodes_TransactionInit_d1g5v0_Shanghai")));
+        assert!(predicate.matches(&case("Opcodes_TransactionInit_d3g0v0_Shanghai")));
+        assert!(!predicate.matches(&case("Opcodes_TransactionInit_d4g0v0_Shanghai")));
+        assert!(!predicate.matches(&case("Opcodes_TransactionInit_d1g0v1_Shanghai")));
+    }
+
+    #[test]
+    fn test_index_predicate_invalid() {
+        for pattern in ["", "x0", "v", "v0v1", "d1-", "g0_v0"] {
+            assert_eq!(IndexPredicate::parse(pattern), None, "{pattern}");
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use super::{negated_regex, Category, Filter, Folder};
+
+/// Regex entry matching a file name already listed in the `filename` mapping
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedundantEntry {
+    /// The folder containing the entries
+    pub folder: Folder,
+    /// The pattern of the regex entry
+    pub pattern: String,
+    /// The file name matched by the regex entry
+    pub filename: String,
+}
+
+impl Filter {
+    /// Returns the entries of the `regex` mapping which match a file name listed
+    /// in the `filename` mapping of the same folder. Negated entries are ignored,
+    /// since they keep the files they match instead of skipping them.
+    pub fn find_redundant(&self) -> Vec<RedundantEntry> {
+        let mut redundant = Vec::new();
+        for (folder, regexes) in &self.regex {
+            let Some((_, filenames)) = self.folder_entries(&self.filename, folder) else {
+                continue;
+            };
+            for (index, regex) in regexes.iter().enumerate() {
+                if negated_regex(regex.pattern()).is_some() {
+                    continue;
+                }
+                let shadowed = filenames.iter().find(|filename| {
+                    self.entry_matches(Category::Regex, folder, index, filename.pattern(), None)
+                });
+                if let Some(filename) = shadowed {
+                    redundant.push(RedundantEntry {
+                        folder: folder.clone(),
+                        pattern: regex.pattern().to_string(),
+                        filename: filename.pattern().to_string(),
+                    });
+                }
+            }
+        }
+        redundant
+    }
+
+    /// Returns the pairs of `regex` entries of a folder where the first pattern
+    /// matches every name matched by the second one. Only patterns made of a
+    /// literal prefix followed by `.*` are considered as subsuming others, so
+    /// some overlaps may go unreported.
+    pub fn find_overlapping_regex(&self) -> Vec<(Folder, String, String)> {
+        let normalize = |pattern: &str| {
+            if self.case_insensitive {
+                pattern.to_lowercase()
+            } else {
+                pattern.to_string()
+            }
+        };
+        let full_match = self.is_full_match();
+
+        let mut overlapping = Vec::new();
+        for (folder, entries) in &self.regex {
+            // Negated patterns keep files instead of skipping them
+            let entries = entries
+                .iter()
+                .filter(|entry| negated_regex(entry.pattern()).is_none())
+                .collect::<Vec<_>>();
+            for broad in &entries {
+                let Some((broad_anchored, prefix)) = prefix_pattern(broad.pattern()) else {
+                    continue;
+                };
+                let prefix = normalize(prefix);
+                for narrow in &entries {
+                    if narrow.pattern() == broad.pattern() {
+                        continue;
+                    }
+                    let (narrow_anchored, literal) = literal_prefix(narrow.pattern());
+                    let literal = normalize(&literal);
+                    let is_subsumed = if full_match || broad_anchored {
+                        (full_match || narrow_anchored) && literal.starts_with(&prefix)
+                    } else {
+                        literal.contains(&prefix)
+                    };
+                    if is_subsumed {
+                        overlapping.push((
+                            folder.clone(),
+                            broad.pattern().to_string(),
+                            narrow.pattern().to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+        overlapping
+    }
+}
+
+/// Splits a pattern of the form `^?<literal>.*$?` into whether it is anchored
+/// at the start and its literal prefix.
+fn prefix_pattern(pattern: &str) -> Option<(bool, &str)> {
+    let (anchored, pattern) = match pattern.strip_prefix('^') {
+        Some(pattern) => (true, pattern),
+        None => (false, pattern),
+    };
+    let pattern = pattern.strip_suffix('$').unwrap_or(pattern);
+    let prefix = pattern.strip_suffix(".*")?;
+    (regex::escape(prefix) == prefix).then_some((anchored, prefix))
+}
+
+/// Returns whether the pattern is anchored at the start and the literal text
+/// every match of the pattern starts with. Patterns with an alternation have no
+/// literal prefix.
+fn literal_prefix(pattern: &str) -> (bool, String) {
+    let (anchored, pattern) = match pattern.strip_prefix('^') {
+        Some(pattern) => (true, pattern),
+        None => (false, pattern),
+    };
+    if pattern.contains('|') {
+        return (anchored, String::new());
+    }
+    let mut literal = String::new();
+    for c in pattern.chars() {
+        match c {
+            // The previous character is optional or repeated
+            '?' | '*' | '{' => {
+                literal.pop();
+                break;
+            }
+            _ if regex::escape(&c.to_string()).len() > 1 => break,
+            _ => literal.push(c),
+        }
+    }
+    (anchored, literal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_redundant() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+              stRandom:
+                - randomStatetest0
+            regex:
+              stCallCreateCallCodeTest:
+                - Call.*
+                - Callcode.*
+                - "!Callcode.*"
+              stBadOpcode:
+                - opc.*
+            testname: {}
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let redundant = filter.find_redundant();
+
+        // Then
+        assert_eq!(
+            redundant,
+            vec![RedundantEntry {
+                folder: "stCallCreateCallCodeTest".to_string(),
+                pattern: "Call.*".to_string(),
+                filename: "Call1024PreCalls".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_find_overlapping_regex() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex:
+              stCallCreateCallCodeTest:
+                - Call.*
+                - Call1024.*
+                - Callcode?.*
+              stRandom:
+                - random.*
+                - Random.*
+            testname: {}
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let overlapping = filter.find_overlapping_regex();
+
+        // Then
+        assert_eq!(
+            overlapping,
+            vec![
+                (
+                    "stCallCreateCallCodeTest".to_string(),
+                    "Call.*".to_string(),
+                    "Call1024.*".to_string()
+                ),
+                (
+                    "stCallCreateCallCodeTest".to_string(),
+                    "Call.*".to_string(),
+                    "Callcode?.*".to_string()
+                ),
+            ]
+        );
+    }
+}
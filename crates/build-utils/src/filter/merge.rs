@@ -0,0 +1,500 @@
+use super::{diff::flatten_fork_map, Category, Filter, FilterMap, Folder, ForkMap, SkipEntry};
+
+/// Error returned by a three-way merge when a folder was deleted on one
+/// side and modified on the other
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("folder {folder} of the `{map}` mapping was deleted on one side and modified on the other")]
+pub struct MergeConflict {
+    /// The conflicting folder
+    pub folder: Folder,
+    /// The name of the mapping containing the folder
+    pub map: &'static str,
+}
+
+/// How [`Filter::merge_preferring`] combines the entries of a folder present in
+/// both filters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// The entries of both filters are kept, skipping the duplicated patterns
+    #[default]
+    Union,
+    /// The entries of the current filter are kept
+    PreferSelf,
+    /// The entries of the other filter replace the ones of the current filter
+    PreferOther,
+}
+
+impl Filter {
+    /// Merges the changes of `ours` and `theirs` relative to their common ancestor
+    /// `base`. For each folder of each mapping, the entries added on either side are
+    /// kept and the entries removed on either side are dropped. A folder deleted on
+    /// one side and modified on the other results in a [`MergeConflict`].
+    pub fn merge3(base: &Self, ours: &Self, theirs: &Self) -> Result<Self, MergeConflict> {
+        let merge = |category: Category| {
+            merge3_map(
+                base.category_map(category),
+                ours.category_map(category),
+                theirs.category_map(category),
+            )
+            .map_err(|folder| MergeConflict {
+                folder,
+                map: category.name(),
+            })
+        };
+        let fork = merge3_map(
+            &flatten_fork_map(&base.fork),
+            &flatten_fork_map(&ours.fork),
+            &flatten_fork_map(&theirs.fork),
+        )
+        .map_err(|folder| MergeConflict {
+            folder,
+            map: "fork",
+        })?;
+        let only =
+            merge3_map(&base.only, &ours.only, &theirs.only).map_err(|folder| MergeConflict {
+                folder,
+                map: "only",
+            })?;
+
+        let keep =
+            merge3_map(&base.keep, &ours.keep, &theirs.keep).map_err(|folder| MergeConflict {
+                folder,
+                map: "keep",
+            })?;
+
+        let extension =
+            merge3_map(&base.extension, &ours.extension, &theirs.extension).map_err(|folder| {
+                MergeConflict {
+                    folder,
+                    map: "extension",
+                }
+            })?;
+
+        let hash =
+            merge3_map(&base.hash, &ours.hash, &theirs.hash).map_err(|folder| MergeConflict {
+                folder,
+                map: "hash",
+            })?;
+
+        Ok(Self {
+            filename: merge(Category::Filename)?,
+            regex: merge(Category::Regex)?,
+            test_name: merge(Category::TestName)?,
+            test_name_regex: merge(Category::TestNameRegex)?,
+            glob: merge(Category::Glob)?,
+            index: merge(Category::Index)?,
+            fork: unflatten_fork_map(fork),
+            skip_forks: merge3_entries(
+                &base.skip_forks,
+                &ours.skip_forks,
+                &theirs.skip_forks,
+                String::as_str,
+            ),
+            folders: pick(&base.folders, &ours.folders, &theirs.folders).clone(),
+            profiles: pick(&base.profiles, &ours.profiles, &theirs.profiles).clone(),
+            only,
+            keep,
+            extension,
+            hash,
+            case_insensitive: pick(
+                base.case_insensitive,
+                ours.case_insensitive,
+                theirs.case_insensitive,
+            ),
+            full_match: pick(base.full_match, ours.full_match, theirs.full_match),
+            regex_engine: pick(base.regex_engine, ours.regex_engine, theirs.regex_engine),
+            match_target: pick(base.match_target, ours.match_target, theirs.match_target),
+            path_scoped: pick(base.path_scoped, ours.path_scoped, theirs.path_scoped),
+            cascade: pick(base.cascade, ours.cascade, theirs.cascade),
+            issue_pattern: pick(
+                &base.issue_pattern,
+                &ours.issue_pattern,
+                &theirs.issue_pattern,
+            )
+            .clone(),
+            require_issues: pick(
+                base.require_issues,
+                ours.require_issues,
+                theirs.require_issues,
+            ),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the filter containing the entries present in both filters, per folder
+    /// and mapping. The settings of the current filter are kept.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let intersect = |category: Category| {
+            intersect_map(self.category_map(category), other.category_map(category))
+        };
+        Self {
+            filename: intersect(Category::Filename),
+            regex: intersect(Category::Regex),
+            test_name: intersect(Category::TestName),
+            test_name_regex: intersect(Category::TestNameRegex),
+            glob: intersect(Category::Glob),
+            index: intersect(Category::Index),
+            fork: unflatten_fork_map(intersect_map(
+                &flatten_fork_map(&self.fork),
+                &flatten_fork_map(&other.fork),
+            )),
+            skip_forks: self
+                .skip_forks
+                .iter()
+                .filter(|fork| other.skip_forks.contains(fork))
+                .cloned()
+                .collect(),
+            only: intersect_map(&self.only, &other.only),
+            keep: intersect_map(&self.keep, &other.keep),
+            extension: intersect_map(&self.extension, &other.extension),
+            hash: intersect_map(&self.hash, &other.hash),
+            folders: self.folders.clone(),
+            profiles: self.profiles.clone(),
+            case_insensitive: self.case_insensitive,
+            full_match: self.full_match,
+            regex_engine: self.regex_engine,
+            match_target: self.match_target,
+            path_scoped: self.path_scoped,
+            cascade: self.cascade,
+            issue_pattern: self.issue_pattern.clone(),
+            require_issues: self.require_issues,
+            ..Default::default()
+        }
+    }
+
+    /// Removes the entries of `other` from the filter, per folder and mapping, and
+    /// drops the folders left empty. This is the inverse of [`merge`](Self::merge),
+    /// the settings of the current filter are kept.
+    pub fn subtract(&mut self, other: &Self) {
+        subtract_map(&mut self.filename, &other.filename);
+        subtract_map(&mut self.regex, &other.regex);
+        subtract_map(&mut self.test_name, &other.test_name);
+        subtract_map(&mut self.test_name_regex, &other.test_name_regex);
+        subtract_map(&mut self.glob, &other.glob);
+        subtract_map(&mut self.index, &other.index);
+        subtract_map(&mut self.only, &other.only);
+        subtract_map(&mut self.keep, &other.keep);
+        subtract_map(&mut self.extension, &other.extension);
+        subtract_map(&mut self.hash, &other.hash);
+        for (folder, forks) in &mut self.fork {
+            if let Some(other_forks) = other.fork.get(folder) {
+                subtract_map(forks, other_forks);
+            }
+        }
+        self.fork.retain(|_, forks| !forks.is_empty());
+        self.skip_forks
+            .retain(|fork| !other.skip_forks.contains(fork));
+        self.reset_caches();
+    }
+}
+
+/// Removes from `lhs` the entries with a pattern present in the same folder of `rhs`,
+/// dropping the folders left empty.
+fn subtract_map(lhs: &mut FilterMap, rhs: &FilterMap) {
+    for (folder, entries) in lhs.iter_mut() {
+        if let Some(rhs_entries) = rhs.get(folder) {
+            entries.retain(|entry| !rhs_entries.iter().any(|e| e.pattern() == entry.pattern()));
+        }
+    }
+    lhs.retain(|_, entries| !entries.is_empty());
+}
+
+/// Returns the entries of `lhs` with a pattern also present in the same folder of `rhs`.
+fn intersect_map(lhs: &FilterMap, rhs: &FilterMap) -> FilterMap {
+    lhs.iter()
+        .filter_map(|(folder, entries)| {
+            let rhs_entries = rhs.get(folder)?;
+            let entries: Vec<_> = entries
+                .iter()
+                .filter(|entry| rhs_entries.iter().any(|e| e.pattern() == entry.pattern()))
+                .cloned()
+                .collect();
+            (!entries.is_empty()).then(|| (folder.clone(), entries))
+        })
+        .collect()
+}
+
+/// Three-way merge of a setting, preferring the side which changed it.
+fn pick<T: PartialEq>(base: T, ours: T, theirs: T) -> T {
+    if ours != base {
+        ours
+    } else {
+        theirs
+    }
+}
+
+/// Three-way merge of the mappings, returning the conflicting folder on error.
+fn merge3_map(base: &FilterMap, ours: &FilterMap, theirs: &FilterMap) -> Result<FilterMap, Folder> {
+    let mut merged = FilterMap::new();
+    let folders = base.keys().chain(ours.keys()).chain(theirs.keys());
+    for folder in folders {
+        if merged.contains_key(folder) {
+            continue;
+        }
+        let entries = match (base.get(folder), ours.get(folder), theirs.get(folder)) {
+            (Some(_), None, None) => continue,
+            (Some(base), None, Some(entries)) | (Some(base), Some(entries), None) => {
+                if !same_patterns(base, entries) {
+                    return Err(folder.clone());
+                }
+                continue;
+            }
+            (base, ours, theirs) => merge3_entries(
+                base.map_or(&[], Vec::as_slice),
+                ours.map_or(&[], Vec::as_slice),
+                theirs.map_or(&[], Vec::as_slice),
+                SkipEntry::pattern,
+            ),
+        };
+        merged.insert(folder.clone(), entries);
+    }
+    Ok(merged)
+}
+
+/// Three-way merge of the entries, compared by key. Entries of `ours` are kept
+/// unless removed by `theirs`, followed by the entries added by `theirs`.
+fn merge3_entries<T: Clone>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    key: impl Fn(&T) -> &str,
+) -> Vec<T> {
+    let contains = |entries: &[T], entry: &T| entries.iter().any(|e| key(e) == key(entry));
+    let mut merged: Vec<T> = ours
+        .iter()
+        .filter(|entry| !contains(base, entry) || contains(theirs, entry))
+        .cloned()
+        .collect();
+    for entry in theirs {
+        if !contains(base, entry) && !contains(&merged, entry) {
+            merged.push(entry.clone());
+        }
+    }
+    merged
+}
+
+/// Returns true if both lists contain the same patterns, regardless of their order.
+fn same_patterns(lhs: &[SkipEntry], rhs: &[SkipEntry]) -> bool {
+    lhs.iter()
+        .all(|entry| rhs.iter().any(|e| e.pattern() == entry.pattern()))
+        && rhs
+            .iter()
+            .all(|entry| lhs.iter().any(|e| e.pattern() == entry.pattern()))
+}
+
+/// Reverts [`flatten_fork_map`], splitting the `fork/test` entries.
+fn unflatten_fork_map(map: FilterMap) -> ForkMap {
+    let mut forks = ForkMap::new();
+    for (folder, entries) in map {
+        for entry in entries {
+            let Some((fork, test)) = entry.pattern().split_once('/') else {
+                continue;
+            };
+            let test = entry.with_pattern(test.to_string());
+            forks
+                .entry(folder.clone())
+                .or_default()
+                .entry(fork.to_string())
+                .or_default()
+                .push(test);
+        }
+    }
+    forks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(yaml: &str) -> Filter {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_merge3() {
+        // Given
+        let base = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest0, randomStatetest1]
+              stDeleted: [deletedTest]
+            regex: {}
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+            "#,
+        );
+        let ours = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest0, randomStatetest1, randomStatetest2]
+            regex:
+              stBadOpcode: [opc.*]
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+            "#,
+        );
+        let theirs = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest1, randomStatetest3]
+              stDeleted: [deletedTest]
+            regex: {}
+            testname:
+              stTransactionTest: [Opcodes_TransactionInit_d0g0v0_Shanghai]
+            fork:
+              stTransactionTest:
+                Cancun: [Opcodes_TransactionInit]
+                Shanghai: [Opcodes_TransactionInit]
+            "#,
+        );
+
+        // When
+        let merged = Filter::merge3(&base, &ours, &theirs).unwrap();
+
+        // Then
+        assert_eq!(
+            merged.filename,
+            FilterMap::from([(
+                "stRandom".to_string(),
+                vec![
+                    "randomStatetest1".into(),
+                    "randomStatetest2".into(),
+                    "randomStatetest3".into()
+                ]
+            )])
+        );
+        assert_eq!(merged.regex, ours.regex);
+        assert_eq!(merged.test_name, theirs.test_name);
+        assert_eq!(merged.fork, theirs.fork);
+    }
+
+    #[test]
+    fn test_intersect() {
+        // Given
+        let lhs = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest0, randomStatetest1]
+              stBadOpcode: [opc4DDiffPlaces]
+            regex:
+              stMemoryStressTest: [CALL_Bounds.*]
+            testname: {}
+            "#,
+        );
+        let rhs = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest1, randomStatetest2]
+            regex:
+              stMemoryStressTest: [CREATE_Bounds.*]
+            testname:
+              stBadOpcode: [opc4DDiffPlaces_d0g0v0_Shanghai]
+            "#,
+        );
+
+        // When
+        let intersection = lhs.intersect(&rhs);
+
+        // Then
+        assert_eq!(
+            intersection.filename,
+            FilterMap::from([("stRandom".to_string(), vec!["randomStatetest1".into()])])
+        );
+        assert!(intersection.regex.is_empty());
+        assert!(intersection.test_name.is_empty());
+        assert_eq!(intersection.len(), 1);
+    }
+
+    #[test]
+    fn test_subtract() {
+        // Given
+        let mut lhs = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest0, randomStatetest1]
+              stBadOpcode: [opc4DDiffPlaces]
+            regex:
+              stMemoryStressTest: [CALL_Bounds.*]
+            testname: {}
+            fork:
+              stRandom:
+                Cancun: [randomStatetest2]
+            "#,
+        );
+        let fix = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest1]
+              stBadOpcode: [opc4DDiffPlaces]
+            regex:
+              stMemoryStressTest: [CREATE_Bounds.*]
+            testname: {}
+            fork:
+              stRandom:
+                Cancun: [randomStatetest2]
+            "#,
+        );
+
+        // When
+        lhs.subtract(&fix);
+
+        // Then
+        assert_eq!(
+            lhs.filename,
+            FilterMap::from([("stRandom".to_string(), vec!["randomStatetest0".into()])])
+        );
+        assert_eq!(
+            lhs.regex,
+            FilterMap::from([(
+                "stMemoryStressTest".to_string(),
+                vec!["CALL_Bounds.*".into()]
+            )])
+        );
+        assert!(lhs.fork.is_empty());
+        assert_eq!(lhs.len(), 2);
+    }
+
+    #[test]
+    fn test_merge3_conflict() {
+        // Given
+        let base = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest0]
+            regex: {}
+            testname: {}
+            "#,
+        );
+        let ours = filter(
+            r#"
+            filename: {}
+            regex: {}
+            testname: {}
+            "#,
+        );
+        let theirs = filter(
+            r#"
+            filename:
+              stRandom: [randomStatetest0, randomStatetest1]
+            regex: {}
+            testname: {}
+            "#,
+        );
+
+        // When
+        let conflict = Filter::merge3(&base, &ours, &theirs).unwrap_err();
+
+        // Then
+        assert_eq!(
+            conflict,
+            MergeConflict {
+                folder: "stRandom".to_string(),
+                map: "filename",
+            }
+        );
+    }
+}
@@ -0,0 +1,4464 @@
+use chrono::NaiveDate;
+use glob::{MatchOptions, Pattern};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::SystemTime,
+};
+
+use self::{diff::flatten_fork_map, engine::CompiledRegex, index::IndexPredicate};
+use crate::{
+    case::parse_case_indices,
+    constants::{
+        ALL_FILES, BLOCKCHAIN_TESTS, CASE_INDICES, ENV_PLACEHOLDER, ISSUE_REFERENCE,
+        WILDCARD_FOLDER,
+    },
+    fork::{parse_fork, Fork},
+    path::PathWrapper,
+};
+
+mod builder;
+mod decision;
+mod diff;
+mod engine;
+mod entry;
+mod explain;
+mod index;
+mod lint;
+mod merge;
+mod plan;
+mod report;
+mod scoped;
+mod text;
+mod walk;
+
+pub use builder::FilterBuilder;
+pub use decision::Decision;
+pub use diff::{ChangeSummary, DiffReport, FilterDiff, FilterRelation, FolderDiff, Moved};
+pub use engine::RegexEngine;
+pub use entry::{ExpiredEntry, FilterEntryRef, Severity, SkipEntry, SkipMode};
+pub use explain::Explanation;
+pub use lint::RedundantEntry;
+pub use merge::{MergeConflict, MergePolicy};
+pub use plan::{PlannedCase, RunPlan};
+pub use scoped::ScopedFilter;
+pub use walk::{SkipStats, StaleEntry};
+
+type Folder = String;
+type FilterMap = BTreeMap<Folder, Vec<SkipEntry>>;
+type ForkMap = BTreeMap<Folder, BTreeMap<String, Vec<SkipEntry>>>;
+type RegexMap = BTreeMap<Folder, Vec<CompiledRegex>>;
+type GlobMap = BTreeMap<Folder, Vec<Pattern>>;
+type FilterCache = HashMap<String, (SystemTime, Arc<Filter>)>;
+
+/// Environment variable overriding the path of the filter file
+pub const SKIP_FILE_ENV: &str = "EF_TESTS_SKIP_FILE";
+
+/// Latest version of the filter file format
+pub const FILTER_VERSION: u32 = 1;
+
+/// Baseline skip list compiled into the crate
+const EMBEDDED_FILTER: &str = include_str!("../../blockchain-tests-skip.yml");
+
+/// Error returned when the filter can't be loaded or is invalid
+#[derive(Debug, thiserror::Error)]
+pub enum FilterError {
+    /// The filter file couldn't be read
+    #[error("unable to read filter file {}: {error}", .path.display())]
+    Io {
+        /// The path of the filter file
+        path: PathBuf,
+        /// The IO error
+        #[source]
+        error: std::io::Error,
+    },
+    /// The filter file has an extension other than `.yml`, `.yaml` or `.toml`
+    #[error("Unsupported filter file {}, expected a .yml, .yaml or .toml extension", .path.display())]
+    UnsupportedFormat {
+        /// The path of the filter file
+        path: PathBuf,
+    },
+    /// The filter isn't valid YAML or TOML, or doesn't have the shape of a filter
+    #[error("invalid filter{}: {error}", file_context(.path))]
+    Parse {
+        /// The path of the filter file, if the filter was loaded from a file
+        path: Option<PathBuf>,
+        /// The deserialization error
+        #[source]
+        error: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// The filter file was parsed but failed [validation](Filter::validate)
+    #[error("error in filter file {}: {error}", .path.display())]
+    Validation {
+        /// The path of the filter file
+        path: PathBuf,
+        /// The validation error
+        #[source]
+        error: Box<FilterError>,
+    },
+    /// A filter file is part of its own `extends` chain
+    #[error("filter file {} extends itself", .path.display())]
+    ExtendsCycle {
+        /// The path of the filter file
+        path: PathBuf,
+    },
+    /// A regex pattern from the `regex` mapping failed to compile
+    #[error("invalid regex pattern `{pattern}` in folder {folder}: {error}")]
+    InvalidRegex {
+        /// The folder containing the pattern
+        folder: Folder,
+        /// The offending pattern
+        pattern: String,
+        /// The compilation error
+        #[source]
+        error: regex::Error,
+    },
+    /// A regex pattern failed to compile with the `fancy` engine
+    #[cfg(feature = "fancy-regex")]
+    #[error("invalid fancy regex pattern `{pattern}` in folder {folder}: {error}")]
+    InvalidFancyRegex {
+        /// The folder containing the pattern
+        folder: Folder,
+        /// The offending pattern
+        pattern: String,
+        /// The compilation error
+        #[source]
+        error: Box<fancy_regex::Error>,
+    },
+    /// The `fancy` regex engine is selected but the `fancy-regex` feature is disabled
+    #[error("the `fancy` regex engine requires the `fancy-regex` feature")]
+    FancyRegexUnavailable,
+    /// A glob pattern from the `glob` mapping failed to compile
+    #[error("invalid glob pattern `{pattern}` in folder {folder}: {error}")]
+    InvalidGlob {
+        /// The folder containing the pattern
+        folder: Folder,
+        /// The offending pattern
+        pattern: String,
+        /// The compilation error
+        #[source]
+        error: glob::PatternError,
+    },
+    /// A pattern is listed more than once in the same folder of a mapping
+    #[error("duplicate pattern `{pattern}` in folder {folder} of the `{map}` mapping")]
+    Duplicate {
+        /// The folder containing the pattern
+        folder: Folder,
+        /// The duplicated pattern, as `fork/test` for the `fork` mapping
+        pattern: String,
+        /// The name of the mapping containing the pattern
+        map: &'static str,
+    },
+    /// A predicate from the `index` mapping failed to parse
+    #[error(
+        "invalid index predicate `{pattern}` in folder {folder}, expected e.g. `v0` or `d1-3g0`"
+    )]
+    InvalidIndex {
+        /// The folder containing the predicate
+        folder: Folder,
+        /// The offending predicate
+        pattern: String,
+    },
+    /// The `issue_pattern` setting failed to compile
+    #[error("invalid issue pattern: {error}")]
+    InvalidIssuePattern {
+        /// The compilation error
+        #[source]
+        error: regex::Error,
+    },
+    /// The issue reference of an entry doesn't match the issue pattern
+    #[error("invalid issue reference `{issue}` for pattern `{pattern}` in folder {folder}")]
+    InvalidIssue {
+        /// The folder containing the entry
+        folder: Folder,
+        /// The pattern of the entry
+        pattern: String,
+        /// The offending issue reference
+        issue: String,
+    },
+    /// An entry has no issue reference while `require_issues` is set
+    #[error("missing issue reference for pattern `{pattern}` in folder {folder}")]
+    MissingIssue {
+        /// The folder containing the entry
+        folder: Folder,
+        /// The pattern of the entry
+        pattern: String,
+    },
+    /// An entry of the `hash` mapping isn't a hex encoded SHA-256 digest
+    #[error("invalid hash `{pattern}` in folder {folder}, expected a hex encoded SHA-256 digest")]
+    InvalidHash {
+        /// The folder containing the entry
+        folder: Folder,
+        /// The offending digest
+        pattern: String,
+    },
+    /// The filter file declares a version newer than the supported one
+    #[error(
+        "unsupported filter version {version}, the latest supported version is {FILTER_VERSION}"
+    )]
+    UnsupportedVersion {
+        /// The version declared by the filter file
+        version: u32,
+    },
+}
+
+/// Returns the ` file <path>` suffix naming the filter file in error messages, or an
+/// empty string for a filter parsed from a string.
+fn file_context(path: &Option<PathBuf>) -> String {
+    path.as_ref()
+        .map(|path| format!(" file {}", path.display()))
+        .unwrap_or_default()
+}
+
+/// Category of a filter entry, corresponding to the mapping containing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub enum Category {
+    /// Entry of the `filename` mapping
+    #[serde(rename = "filename")]
+    Filename,
+    /// Entry of the `regex` mapping
+    #[serde(rename = "regex")]
+    Regex,
+    /// Entry of the `glob` mapping
+    #[serde(rename = "glob")]
+    Glob,
+    /// Entry of the `testname` mapping
+    #[serde(rename = "testname")]
+    TestName,
+    /// Entry of the `testname_regex` mapping
+    #[serde(rename = "testname_regex")]
+    TestNameRegex,
+    /// Entry of the `index` mapping
+    #[serde(rename = "index")]
+    Index,
+}
+
+impl Category {
+    /// All the categories, in the order in which they are checked
+    pub const ALL: [Self; 6] = [
+        Self::Filename,
+        Self::Regex,
+        Self::Glob,
+        Self::TestName,
+        Self::TestNameRegex,
+        Self::Index,
+    ];
+
+    /// Returns the name of the mapping in the filter file
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Filename => "filename",
+            Self::Regex => "regex",
+            Self::Glob => "glob",
+            Self::TestName => "testname",
+            Self::TestNameRegex => "testname_regex",
+            Self::Index => "index",
+        }
+    }
+
+    /// Returns true if the entries of the category are matched against the case name
+    pub const fn matches_case_name(self) -> bool {
+        matches!(self, Self::TestName | Self::TestNameRegex | Self::Index)
+    }
+}
+
+/// Reason for which a test is skipped by the filter, along with the matching entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// The file name is listed in the `filename` mapping
+    Filename(SkipEntry),
+    /// The file name matches the contained pattern from the `regex` mapping
+    Regex(SkipEntry),
+    /// The file name matches the contained pattern from the `glob` mapping
+    Glob(SkipEntry),
+    /// The case name is listed in the `testname` mapping
+    TestName(SkipEntry),
+    /// The case name matches the contained pattern from the `testname_regex` mapping
+    TestNameRegex(SkipEntry),
+    /// The indices of the case name match the contained predicate from the `index` mapping
+    Index(SkipEntry),
+    /// The base test name is listed under the case's fork in the `fork` mapping
+    Fork {
+        /// The fork parsed from the case name
+        fork: String,
+        /// The matching base test name
+        test: SkipEntry,
+    },
+    /// A component of the path is listed in `skip_forks`
+    ForkPath(SkipEntry),
+    /// The file extension is listed in the `extension` mapping
+    Extension(SkipEntry),
+    /// The SHA-256 digest of the file content is listed in the `hash` mapping
+    Hash(SkipEntry),
+}
+
+impl SkipReason {
+    fn new(category: Category, entry: SkipEntry) -> Self {
+        match category {
+            Category::Filename => Self::Filename(entry),
+            Category::Regex => Self::Regex(entry),
+            Category::Glob => Self::Glob(entry),
+            Category::TestName => Self::TestName(entry),
+            Category::TestNameRegex => Self::TestNameRegex(entry),
+            Category::Index => Self::Index(entry),
+        }
+    }
+
+    /// Returns the filter entry which caused the skip
+    pub fn entry(&self) -> &SkipEntry {
+        match self {
+            Self::Filename(entry)
+            | Self::Regex(entry)
+            | Self::Glob(entry)
+            | Self::TestName(entry)
+            | Self::TestNameRegex(entry)
+            | Self::Index(entry)
+            | Self::Fork { test: entry, .. }
+            | Self::ForkPath(entry)
+            | Self::Extension(entry)
+            | Self::Hash(entry) => entry,
+        }
+    }
+
+    /// Returns the severity of the matching entry
+    pub fn severity(&self) -> Severity {
+        self.entry().severity()
+    }
+
+    /// Returns the human-readable reason attached to the matching entry, if any
+    pub fn reason(&self) -> Option<&str> {
+        self.entry().reason()
+    }
+}
+
+/// Reason for which a test is skipped, along with the folder key of the matching entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkipDetail {
+    /// The reason for the skip
+    pub reason: SkipReason,
+    /// The folder key of the matching entry, e.g. `stCreate2`, `*` or a path if the
+    /// filter is path scoped. `None` if the path is skipped by `skip_forks`.
+    pub folder: Option<Folder>,
+}
+
+/// Classification of a test by the filter, built by [`Filter::classify`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Classification {
+    /// The test runs
+    Run,
+    /// The test doesn't run
+    Skip(SkipReason),
+    /// The test runs, but its failure is only reported as a warning
+    WarnOnly(SkipReason),
+}
+
+/// String the file entries of the filter are matched against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchTarget {
+    /// The file stem, e.g. `Call1024PreCalls`
+    #[default]
+    Stem,
+    /// The file name, e.g. `Call1024PreCalls.json`
+    FileName,
+    /// The path relative to the `BlockchainTests` folder (or the full path if absent),
+    /// e.g. `GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json`
+    RelativePath,
+}
+
+impl MatchTarget {
+    /// Returns true for the default target
+    pub fn is_stem(&self) -> bool {
+        *self == Self::Stem
+    }
+}
+
+/// Settings of a folder, applying to all the entries listed under it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct FolderConfig {
+    /// Severity of the bare pattern entries of the folder, defaults to `skip`.
+    /// Detailed entries keep their own severity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_severity: Option<Severity>,
+}
+
+/// Filter to be applied on the tests files.
+///
+/// A test is skipped as soon as it matches any of the `filename`, `regex`,
+/// `glob`, `testname` or `index` mappings. The `filename`, `regex` and `glob` mappings
+/// are matched against the file stem (see [`MatchTarget`]), the `testname` mapping against the case name
+/// and the `index` mapping against the indices of the case name.
+/// The entries of the `*` folder apply to every directory. Files and cases listed
+/// in the `keep` mapping are never skipped. Entries with the `warn` severity
+/// don't skip the tests they match, see [`Filter::classify`].
+#[derive(Debug, Clone, Deserialize, Default, Serialize)]
+pub struct Filter {
+    /// Version of the filter file format. A missing version is treated as version 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<u32>,
+    /// Paths of the filter files this filter extends, relative to its own file. Only
+    /// resolved by [`Filter::load_file`]: the entries of the extended filters are
+    /// added to the ones of this filter, whose settings take precedence.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    extends: Vec<String>,
+    /// Named sets of rules, e.g. `ci` or `nightly`, added to the rules of the filter
+    /// by [`Filter::for_profile`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    profiles: BTreeMap<String, Filter>,
+    /// Mapping containing the directories and the files that should be skipped.
+    /// The reserved `__all__` entry skips every file of the directory.
+    #[serde(default)]
+    filename: FilterMap,
+    /// Mapping containing the directories and the regex patterns that should be skipped.
+    /// A pattern with a leading `!` is negated: the folder then skips every file which
+    /// matches none of its negated patterns, and the files matching a negated pattern
+    /// aren't skipped by the other patterns of the folder either. Negations only apply
+    /// to the entries of their own folder. Use `[!]` to match a literal leading `!`.
+    #[serde(default)]
+    regex: FilterMap,
+    /// Mapping containing the directories and the specific tests that should be skipped.
+    /// A `*` in an entry matches any sequence of characters, e.g. `Opcodes_TransactionInit_*`.
+    #[serde(rename = "testname", alias = "test_name", default)]
+    test_name: FilterMap,
+    /// Mapping containing the directories and the regex patterns of the tests that should be skipped
+    #[serde(
+        rename = "testname_regex",
+        alias = "test_name_regex",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    test_name_regex: FilterMap,
+    /// Mapping containing the directories and the glob patterns that should be skipped
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    glob: FilterMap,
+    /// Mapping containing the directories and the predicates on the `d<N>g<N>v<N>`
+    /// indices of the tests that should be skipped, e.g. `v0` or `d1-3g0`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    index: FilterMap,
+    /// Mapping containing the directories, the forks and the base tests names that should
+    /// be skipped for that fork. The base test name is the case name without its fork
+    /// suffix, optionally also stripped of its `d<N>g<N>v<N>` indices.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    fork: ForkMap,
+    /// Mapping containing the directories and the extensions of the files that should
+    /// be skipped, with or without the leading dot, e.g. `yml`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    extension: FilterMap,
+    /// Mapping containing the directories and the hex encoded SHA-256 digests of the
+    /// content of the files that should be skipped, so that a rewritten file runs again
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    hash: FilterMap,
+    /// Forks for which all the files under a directory named after the fork should
+    /// be skipped, e.g. `cancun` for `Pyspecs/cancun/...`. Always matched ignoring the case.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    skip_forks: Vec<String>,
+    /// Mapping containing the directories and their settings, e.g. the default
+    /// severity of their entries
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    folders: BTreeMap<Folder, FolderConfig>,
+    /// Mapping containing the directories and the only files or tests that should be ran.
+    /// An empty mapping includes everything.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    only: FilterMap,
+    /// Mapping containing the directories and the files or tests that should be ran even
+    /// if they match a skip rule. The `keep` mapping always wins over the skip rules.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    keep: FilterMap,
+    /// Whether the folders, files and tests names should be matched ignoring the case
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    case_insensitive: bool,
+    /// Whether the regex patterns should match the whole file or case name instead of
+    /// any substring of it. Defaults to true, set it to false to restore substring matching.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    full_match: Option<bool>,
+    /// Engine compiling the regex patterns. The `fancy` engine supports lookarounds
+    /// but requires the `fancy-regex` feature.
+    #[serde(default, skip_serializing_if = "RegexEngine::is_default")]
+    regex_engine: RegexEngine,
+    /// String the `filename`, `regex`, `glob`, `only` and `keep` entries are matched
+    /// against. Defaults to the file stem.
+    #[serde(default, skip_serializing_if = "MatchTarget::is_stem")]
+    match_target: MatchTarget,
+    /// Whether the folders are keyed by their path relative to the tests root
+    /// instead of their name
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    path_scoped: bool,
+    /// Whether the entries of a folder also apply to the files of its subdirectories,
+    /// e.g. `Pyspecs` for `Pyspecs/cancun/...`. This allows writing rules at the level
+    /// of a category of tests, such as the grandparent directory of the test files.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    cascade: bool,
+    /// Pattern the issue references of the entries must match, defaults to a `#123`
+    /// reference or a GitHub issue URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    issue_pattern: Option<String>,
+    /// Whether every skip entry must reference an issue, including the bare patterns
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    require_issues: bool,
+    /// Path of the file the filter was loaded from, named in the panic messages of
+    /// the patterns failing to compile
+    #[serde(skip)]
+    source: Option<PathBuf>,
+    /// Compiled regex patterns, lazily built from the `regex` mapping
+    #[serde(skip)]
+    regex_cache: OnceLock<RegexMap>,
+    /// Compiled regex patterns, lazily built from the `testname_regex` mapping
+    #[serde(skip)]
+    test_name_regex_cache: OnceLock<RegexMap>,
+    /// Compiled glob patterns, lazily built from the `glob` mapping
+    #[serde(skip)]
+    glob_cache: OnceLock<GlobMap>,
+}
+
+/// Filters are compared and hashed on their entries and settings, ignoring the
+/// compiled patterns.
+impl PartialEq for Filter {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Filter {}
+
+impl Hash for Filter {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl Filter {
+    /// Returns the baseline filter compiled into the crate.
+    pub fn embedded() -> Self {
+        serde_yaml::from_str(EMBEDDED_FILTER).expect("Error parsing the embedded filter")
+    }
+
+    /// Loads the filter file at the given path, falling back to the
+    /// [embedded](Self::embedded) filter if the file doesn't exist.
+    pub fn load_or_default(path: &str) -> Result<Self, FilterError> {
+        if !Path::new(path).exists() {
+            log::warn!("Filter file {path} not found, using the embedded filter");
+            return Ok(Self::embedded());
+        }
+        Self::load_file(path)
+    }
+
+    /// Loads the filter file at the path given by the `EF_TESTS_SKIP_FILE`
+    /// environment variable, or at the default path if the variable is unset.
+    pub fn load_from_env(default: &str) -> Result<Self, FilterError> {
+        Self::load_from_env_with(default, |key| std::env::var(key).ok())
+    }
+
+    /// Loads the filter file like [`Filter::load_from_env`], looking the
+    /// `EF_TESTS_SKIP_FILE` variable up with `lookup`.
+    fn load_from_env_with(
+        default: &str,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, FilterError> {
+        match lookup(SKIP_FILE_ENV) {
+            Some(path) => Self::load_file(&path),
+            None => Self::load_file(default),
+        }
+    }
+
+    /// Loads the filter file at the given path, choosing the format from its
+    /// extension: `.yml` and `.yaml` files are parsed as YAML, `.toml` files as TOML.
+    /// The filter files listed in `extends` are loaded and [merged](Self::merge)
+    /// into it.
+    pub fn load_file(path: &str) -> Result<Self, FilterError> {
+        Self::load_file_extending(path, &mut Vec::new())
+    }
+
+    /// Loads the filter file at the given path along with the filters it extends,
+    /// given the canonical paths of the files extending it.
+    fn load_file_extending(path: &str, extending: &mut Vec<PathBuf>) -> Result<Self, FilterError> {
+        let io_error = |error| FilterError::Io {
+            path: PathBuf::from(path),
+            error,
+        };
+        let canonical_path = fs::canonicalize(path).map_err(io_error)?;
+        if extending.contains(&canonical_path) {
+            return Err(FilterError::ExtendsCycle {
+                path: PathBuf::from(path),
+            });
+        }
+
+        let content = fs::read_to_string(path).map_err(io_error)?;
+        let filter: Result<Self, Box<dyn std::error::Error + Send + Sync>> =
+            match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+                Some("yml" | "yaml") => serde_yaml::from_str(&content).map_err(Into::into),
+                Some("toml") => toml::from_str(&content).map_err(Into::into),
+                _ => {
+                    return Err(FilterError::UnsupportedFormat {
+                        path: PathBuf::from(path),
+                    })
+                }
+            };
+        let filter = filter.map_err(|error| FilterError::Parse {
+            path: Some(PathBuf::from(path)),
+            error,
+        })?;
+        let mut filter = Self::prepare(filter).map_err(|error| FilterError::Validation {
+            path: PathBuf::from(path),
+            error: Box::new(error),
+        })?;
+
+        extending.push(canonical_path);
+        let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+        for base in std::mem::take(&mut filter.extends) {
+            let base_path = dir.join(base);
+            filter.merge(Self::load_file_extending(
+                &base_path.to_string_lossy(),
+                extending,
+            )?);
+        }
+        extending.pop();
+
+        filter.source = Some(PathBuf::from(path));
+        Ok(filter)
+    }
+
+    /// Returns the path of the file the filter was loaded from by [`Filter::load_file`], if any.
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    /// Parses a YAML filter, e.g. one embedded with `include_str!`.
+    ///
+    /// ```
+    /// use build_utils::{filter::Filter, path::PathWrapper};
+    /// use std::path::PathBuf;
+    ///
+    /// let filter = Filter::from_yaml_str(
+    ///     "
+    /// filename:
+    ///   stRandom:
+    ///     - randomStatetest0
+    /// regex: {}
+    /// testname: {}
+    /// ",
+    /// )
+    /// .unwrap();
+    ///
+    /// let path = PathWrapper::from(PathBuf::from("GeneralStateTests/stRandom/randomStatetest0.json"));
+    /// assert!(filter.is_skipped(&path, None));
+    /// ```
+    pub fn from_yaml_str(content: &str) -> Result<Self, FilterError> {
+        let filter = serde_yaml::from_str(content).map_err(|error| FilterError::Parse {
+            path: None,
+            error: Box::new(error),
+        })?;
+        Self::prepare(filter)
+    }
+
+    /// Parses a TOML filter, e.g. one embedded with `include_str!`.
+    pub fn from_toml_str(content: &str) -> Result<Self, FilterError> {
+        let filter = toml::from_str(content).map_err(|error| FilterError::Parse {
+            path: None,
+            error: Box::new(error),
+        })?;
+        Self::prepare(filter)
+    }
+
+    /// Expands the environment placeholders of a parsed filter and validates it.
+    fn prepare(mut filter: Self) -> Result<Self, FilterError> {
+        filter.expand_env(&|key| std::env::var(key).ok());
+        filter.warn_substring_patterns();
+        filter.validate()?;
+        Ok(filter)
+    }
+
+    /// Returns the rules of the filter merged with the rules of the named profile,
+    /// whose settings take precedence, or `None` if the filter has no such profile.
+    pub fn for_profile(&self, name: &str) -> Option<Self> {
+        let mut base = self.clone();
+        let profiles = std::mem::take(&mut base.profiles);
+        let mut filter = profiles.get(name)?.clone();
+        filter.profiles.clear();
+        filter.source = base.source.clone();
+        filter.merge(base);
+        Some(filter)
+    }
+
+    /// Returns the names of the profiles of the filter
+    pub fn profiles(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Loads the filter file at the given path, reusing the filter loaded by a
+    /// previous call if the modification time of the file didn't change.
+    pub fn load_file_cached(path: &str) -> Result<Arc<Self>, eyre::Error> {
+        static CACHE: OnceLock<Mutex<FilterCache>> = OnceLock::new();
+
+        let modified = fs::metadata(path)?.modified()?;
+        let mut cache = CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        if let Some((cached_modified, filter)) = cache.get(path) {
+            if *cached_modified == modified {
+                return Ok(filter.clone());
+            }
+        }
+
+        let filter = Arc::new(Self::load_file(path)?);
+        cache.insert(path.to_string(), (modified, filter.clone()));
+        Ok(filter)
+    }
+
+    /// Expands the `${VAR}` placeholders of the patterns with the values given by
+    /// `lookup`, the environment when loading a file. Entries with a placeholder of
+    /// an unset variable, or whose pattern ends up empty, are removed so that they
+    /// match nothing.
+    fn expand_env(&mut self, lookup: &dyn Fn(&str) -> Option<String>) {
+        let expand_map = |map: &mut FilterMap| {
+            for entries in map.values_mut() {
+                *entries = entries
+                    .iter()
+                    .filter_map(|entry| {
+                        if !ENV_PLACEHOLDER.is_match(entry.pattern()) {
+                            return Some(entry.clone());
+                        }
+                        expand_env_placeholders(entry.pattern(), lookup)
+                            .map(|pattern| entry.with_pattern(pattern))
+                    })
+                    .collect();
+            }
+        };
+        expand_map(&mut self.filename);
+        expand_map(&mut self.regex);
+        expand_map(&mut self.test_name);
+        expand_map(&mut self.test_name_regex);
+        expand_map(&mut self.glob);
+        expand_map(&mut self.index);
+        expand_map(&mut self.only);
+        expand_map(&mut self.keep);
+        expand_map(&mut self.extension);
+        expand_map(&mut self.hash);
+        self.fork.values_mut().for_each(expand_map);
+        self.profiles
+            .values_mut()
+            .for_each(|profile| profile.expand_env(lookup));
+        self.skip_forks = self
+            .skip_forks
+            .iter()
+            .filter_map(|fork| expand_env_placeholders(fork, lookup))
+            .collect();
+        self.reset_caches();
+    }
+
+    /// Loads the filter file at the given path, rejecting files which list the
+    /// same pattern more than once in a folder.
+    pub fn load_file_strict(path: &str) -> Result<Self, FilterError> {
+        let filter = Self::load_file(path)?;
+        filter
+            .check_duplicates()
+            .map_err(|error| FilterError::Validation {
+                path: PathBuf::from(path),
+                error: Box::new(error),
+            })?;
+        Ok(filter)
+    }
+
+    /// Checks that no pattern is listed more than once in the same folder of a
+    /// mapping, the `fork` mapping being checked as `fork/test` entries.
+    fn check_duplicates(&self) -> Result<(), FilterError> {
+        let fork = flatten_fork_map(&self.fork);
+        for (map, folders) in self.named_maps().chain([("fork", &fork)]) {
+            for (folder, entries) in folders {
+                let mut seen = BTreeSet::new();
+                if let Some(entry) = entries.iter().find(|entry| !seen.insert(entry.pattern())) {
+                    return Err(FilterError::Duplicate {
+                        folder: folder.clone(),
+                        pattern: entry.pattern().to_string(),
+                        map,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the filter to the given path. Entries are sorted beforehand in
+    /// order to produce a canonical output.
+    pub fn save_file(&self, path: &str) -> Result<(), eyre::Error> {
+        let mut filter = self.clone();
+        filter.sort();
+        fs::write(path, serde_yaml::to_string(&filter)?)?;
+        Ok(())
+    }
+
+    /// Sorts the entries of each folder by pattern, then by metadata.
+    fn sort(&mut self) {
+        let sort_map = |map: &mut FilterMap| {
+            map.values_mut().for_each(|entries| {
+                entries.sort_by(|a, b| a.pattern().cmp(b.pattern()).then_with(|| a.cmp(b)))
+            })
+        };
+        sort_map(&mut self.filename);
+        sort_map(&mut self.regex);
+        sort_map(&mut self.test_name);
+        sort_map(&mut self.test_name_regex);
+        sort_map(&mut self.glob);
+        sort_map(&mut self.index);
+        sort_map(&mut self.only);
+        sort_map(&mut self.keep);
+        sort_map(&mut self.extension);
+        sort_map(&mut self.hash);
+        self.fork.values_mut().for_each(sort_map);
+        self.reset_caches();
+    }
+
+    /// Normalizes the filter: entries are sorted by pattern then by metadata, entries
+    /// equal to another one of their folder are removed and so are the empty folders.
+    /// Entries sharing a pattern but not their metadata are all kept.
+    /// Combined with [`save_file`](Self::save_file), this produces a canonical file.
+    pub fn normalize(&mut self) {
+        self.sort();
+        let normalize_map = |map: &mut FilterMap| {
+            map.values_mut().for_each(|entries| entries.dedup());
+            map.retain(|_, entries| !entries.is_empty());
+        };
+        normalize_map(&mut self.filename);
+        normalize_map(&mut self.regex);
+        normalize_map(&mut self.test_name);
+        normalize_map(&mut self.test_name_regex);
+        normalize_map(&mut self.glob);
+        normalize_map(&mut self.index);
+        normalize_map(&mut self.only);
+        normalize_map(&mut self.keep);
+        normalize_map(&mut self.extension);
+        normalize_map(&mut self.hash);
+        self.fork.values_mut().for_each(normalize_map);
+        self.fork.retain(|_, forks| !forks.is_empty());
+        self.skip_forks.sort();
+        self.skip_forks.dedup();
+        self.profiles.values_mut().for_each(Self::normalize);
+    }
+
+    /// Returns a copy of the filter without the reasons, expiry dates and issue
+    /// references of its entries, so that comparisons only consider the metadata
+    /// changing which tests run: the severity and the skip mode.
+    pub fn without_reasons(&self) -> Self {
+        let mut filter = self.clone();
+        let strip_map = |map: &mut FilterMap| {
+            map.values_mut()
+                .flatten()
+                .for_each(|entry| *entry = entry.without_reasons());
+        };
+        strip_map(&mut filter.filename);
+        strip_map(&mut filter.regex);
+        strip_map(&mut filter.test_name);
+        strip_map(&mut filter.test_name_regex);
+        strip_map(&mut filter.glob);
+        strip_map(&mut filter.index);
+        strip_map(&mut filter.only);
+        strip_map(&mut filter.keep);
+        strip_map(&mut filter.extension);
+        strip_map(&mut filter.hash);
+        filter.fork.values_mut().for_each(strip_map);
+        filter
+    }
+
+    /// Returns the hex encoded SHA-256 hash of the [normalized](Self::normalize)
+    /// filter. The fingerprint doesn't depend on the order of the entries but changes
+    /// whenever an entry or a setting does.
+    pub fn fingerprint(&self) -> String {
+        let mut filter = self.clone();
+        filter.normalize();
+        let bytes = serde_json::to_vec(&filter).expect("Error serializing the filter");
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    /// Loads the filter files at the given paths and merges them in order.
+    pub fn load_files(paths: &[&str]) -> Result<Self, eyre::Error> {
+        let mut filter = Self::default();
+        for path in paths {
+            filter.merge(Self::load_file(path)?);
+        }
+        Ok(filter)
+    }
+
+    /// Loads every `*.yml`, `*.yaml` or `*.toml` filter file in the given directory
+    /// (non recursively) and merges them in sorted file name order.
+    pub fn load_dir(dir: &str) -> Result<Self, eyre::Error> {
+        let mut paths = fs::read_dir(dir)?
+            .map(|entry| Ok(entry?.path()))
+            .collect::<Result<Vec<_>, std::io::Error>>()?;
+        paths.retain(|path| {
+            path.is_file()
+                && path
+                    .extension()
+                    .map_or(false, |ext| ext == "yml" || ext == "yaml" || ext == "toml")
+        });
+        paths.sort();
+
+        let mut filter = Self::default();
+        for path in paths {
+            let path = path.to_string_lossy();
+            let other = Self::load_file(&path)
+                .map_err(|err| eyre::eyre!("Error loading filter file {path}: {err}"))?;
+            filter.merge(other);
+        }
+        Ok(filter)
+    }
+
+    /// Merges the other filter into the current one. Entries of folders
+    /// present in both filters are concatenated, skipping entries with a
+    /// pattern already present in the current filter.
+    pub fn merge(&mut self, other: Self) {
+        self.merge_preferring(other, MergePolicy::Union);
+    }
+
+    /// Merges the other filter into the current one, combining the entries of the
+    /// folders present in both filters according to the given policy. For the `fork`
+    /// mapping, the policy applies to the entries of each fork of a folder. The
+    /// settings are merged as in [`merge`](Self::merge).
+    pub fn merge_preferring(&mut self, other: Self, policy: MergePolicy) {
+        merge_map(&mut self.filename, other.filename, policy);
+        merge_map(&mut self.regex, other.regex, policy);
+        merge_map(&mut self.test_name, other.test_name, policy);
+        merge_map(&mut self.test_name_regex, other.test_name_regex, policy);
+        merge_map(&mut self.glob, other.glob, policy);
+        merge_map(&mut self.index, other.index, policy);
+        merge_map(&mut self.only, other.only, policy);
+        merge_map(&mut self.keep, other.keep, policy);
+        merge_map(&mut self.extension, other.extension, policy);
+        merge_map(&mut self.hash, other.hash, policy);
+        for (folder, forks) in other.fork {
+            let lhs = self.fork.entry(folder).or_default();
+            merge_map(lhs, forks, policy);
+        }
+        for fork in other.skip_forks {
+            if !self.skip_forks.contains(&fork) {
+                self.skip_forks.push(fork);
+            }
+        }
+        for (folder, config) in other.folders {
+            self.folders.entry(folder).or_insert(config);
+        }
+        for (name, profile) in other.profiles {
+            self.profiles
+                .entry(name)
+                .or_default()
+                .merge_preferring(profile, policy);
+        }
+        self.version = self.version.max(other.version);
+        self.case_insensitive |= other.case_insensitive;
+        self.full_match = self.full_match.or(other.full_match);
+        if self.regex_engine.is_default() {
+            self.regex_engine = other.regex_engine;
+        }
+        if self.match_target.is_stem() {
+            self.match_target = other.match_target;
+        }
+        self.path_scoped |= other.path_scoped;
+        self.cascade |= other.cascade;
+        self.issue_pattern = self.issue_pattern.take().or(other.issue_pattern);
+        self.require_issues |= other.require_issues;
+        self.reset_caches();
+    }
+
+    /// Returns the entries and settings of the filter, used to compare and hash it.
+    #[allow(clippy::type_complexity)]
+    fn key(
+        &self,
+    ) -> (
+        (
+            &FilterMap,
+            &FilterMap,
+            &FilterMap,
+            &FilterMap,
+            &FilterMap,
+            &FilterMap,
+        ),
+        (
+            &ForkMap,
+            &[String],
+            &FilterMap,
+            &FilterMap,
+            &FilterMap,
+            &FilterMap,
+            &BTreeMap<Folder, FolderConfig>,
+            &BTreeMap<String, Filter>,
+        ),
+        (
+            Option<u32>,
+            bool,
+            Option<bool>,
+            RegexEngine,
+            MatchTarget,
+            bool,
+            bool,
+            Option<&str>,
+            bool,
+        ),
+    ) {
+        (
+            (
+                &self.filename,
+                &self.regex,
+                &self.test_name,
+                &self.test_name_regex,
+                &self.glob,
+                &self.index,
+            ),
+            (
+                &self.fork,
+                &self.skip_forks,
+                &self.only,
+                &self.keep,
+                &self.extension,
+                &self.hash,
+                &self.folders,
+                &self.profiles,
+            ),
+            (
+                self.version,
+                self.case_insensitive,
+                self.full_match,
+                self.regex_engine,
+                self.match_target,
+                self.path_scoped,
+                self.cascade,
+                self.issue_pattern.as_deref(),
+                self.require_issues,
+            ),
+        )
+    }
+
+    /// Resets the compiled patterns, which need to be rebuilt after a mutation.
+    fn reset_caches(&mut self) {
+        self.regex_cache = OnceLock::new();
+        self.test_name_regex_cache = OnceLock::new();
+        self.glob_cache = OnceLock::new();
+    }
+
+    /// Checks that every pattern in the `regex`, `testname_regex` and `glob` mappings
+    /// compiles, that every predicate of the `index` mapping parses and that every
+    /// entry of the `hash` mapping is a SHA-256 digest.
+    pub fn validate(&self) -> Result<(), FilterError> {
+        if let Some(version) = self.version.filter(|version| *version > FILTER_VERSION) {
+            return Err(FilterError::UnsupportedVersion { version });
+        }
+        for (folder, entries) in &self.regex {
+            for entry in entries {
+                let pattern = entry.pattern();
+                self.compile_regex(folder, negated_regex(pattern).unwrap_or(pattern))?;
+            }
+        }
+        for (folder, entries) in &self.test_name_regex {
+            for entry in entries {
+                self.compile_regex(folder, entry.pattern())?;
+            }
+        }
+        for (folder, entries) in &self.glob {
+            for entry in entries {
+                Pattern::new(entry.pattern()).map_err(|error| FilterError::InvalidGlob {
+                    folder: folder.clone(),
+                    pattern: entry.pattern().to_string(),
+                    error,
+                })?;
+            }
+        }
+        for (folder, entries) in &self.index {
+            for entry in entries {
+                if IndexPredicate::parse(entry.pattern()).is_none() {
+                    return Err(FilterError::InvalidIndex {
+                        folder: folder.clone(),
+                        pattern: entry.pattern().to_string(),
+                    });
+                }
+            }
+        }
+        for (folder, entries) in &self.hash {
+            for entry in entries {
+                let pattern = entry.pattern();
+                if pattern.len() != 64 || !pattern.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(FilterError::InvalidHash {
+                        folder: folder.clone(),
+                        pattern: pattern.to_string(),
+                    });
+                }
+            }
+        }
+        // Profiles are validated with the settings they inherit from the filter
+        for profile in self.profiles().filter_map(|name| self.for_profile(name)) {
+            profile.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the issue references of the skip entries match the issue pattern.
+    /// Entries without an issue reference are only rejected if `require_issues` is set.
+    pub fn validate_issues(&self) -> Result<(), FilterError> {
+        let issue_pattern = self
+            .issue_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|error| FilterError::InvalidIssuePattern { error })?;
+        let issue_pattern = issue_pattern.as_ref().unwrap_or(&ISSUE_REFERENCE);
+
+        let forks = flatten_fork_map(&self.fork);
+        let entries = Category::ALL
+            .into_iter()
+            .flat_map(|category| self.category_map(category))
+            .chain(&forks)
+            .chain(&self.extension)
+            .chain(&self.hash)
+            .flat_map(|(folder, entries)| entries.iter().map(move |entry| (folder, entry)));
+        for (folder, entry) in entries {
+            let pattern = entry.pattern();
+            match entry.issue() {
+                Some(issue) if !issue_pattern.is_match(issue) => {
+                    return Err(FilterError::InvalidIssue {
+                        folder: folder.to_string(),
+                        pattern: pattern.to_string(),
+                        issue: issue.to_string(),
+                    })
+                }
+                None if self.require_issues => {
+                    return Err(FilterError::MissingIssue {
+                        folder: folder.to_string(),
+                        pattern: pattern.to_string(),
+                    })
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks if the given path is inside the filter object
+    pub fn is_skipped(&self, path: &PathWrapper, case_name: Option<&str>) -> bool {
+        #[cfg(feature = "logging")]
+        let _span = tracing::debug_span!(
+            "is_skipped",
+            path = %path.as_ref().display(),
+            case_name
+        )
+        .entered();
+
+        if !self.may_apply(path) {
+            return false;
+        }
+        let reason = self
+            .skip_detail(path, case_name)
+            .map(|detail| detail.reason);
+        #[cfg(feature = "logging")]
+        tracing::debug!(?reason, "skip decision");
+        reason.map_or(false, |reason| reason.severity().is_skip())
+    }
+
+    /// Checks if the given path is inside the filter object, see [`Filter::is_skipped`].
+    pub fn is_skipped_str(&self, path: &str, case_name: Option<&str>) -> bool {
+        self.is_skipped(&PathWrapper::from(Path::new(path).to_path_buf()), case_name)
+    }
+
+    /// Returns the mode of the entry skipping the given path, if skipped, telling
+    /// whether the test should be omitted or generated as an ignored test.
+    pub fn skip_mode(&self, path: &PathWrapper, case_name: Option<&str>) -> Option<SkipMode> {
+        self.skip_reason(path, case_name)
+            .filter(|reason| reason.severity().is_skip())
+            .map(|reason| reason.entry().mode())
+    }
+
+    /// Classifies the given path: tests matching an entry with the `warn` severity,
+    /// and no entry with the `skip` severity, run with their failures only reported
+    /// as warnings. The `only` mapping isn't considered.
+    pub fn classify(&self, path: &PathWrapper, case_name: Option<&str>) -> Classification {
+        match self.skip_reason(path, case_name) {
+            None => Classification::Run,
+            Some(reason) if reason.severity().is_skip() => Classification::Skip(reason),
+            Some(reason) => Classification::WarnOnly(reason),
+        }
+    }
+
+    /// Checks if the given path is either skipped or not included by the filter.
+    fn is_excluded(&self, path: &PathWrapper, case_name: Option<&str>) -> bool {
+        self.is_skipped(path, case_name) || !self.is_included(path, case_name)
+    }
+
+    /// Returns the reason for which the given path is skipped, if any. Entries with
+    /// the `skip` severity take precedence over the ones with the `warn` severity.
+    pub fn skip_reason(&self, path: &PathWrapper, case_name: Option<&str>) -> Option<SkipReason> {
+        self.skip_detail(path, case_name)
+            .map(|detail| detail.reason)
+    }
+
+    /// Returns the reason for which the given path is skipped along with the
+    /// folder key of the matching entry, if any.
+    pub fn skip_detail(&self, path: &PathWrapper, case_name: Option<&str>) -> Option<SkipDetail> {
+        let dir_names = self.folder_keys(path);
+        let file_name = self.match_name(path);
+        if dir_names
+            .iter()
+            .any(|dir_name| self.is_kept(dir_name, &file_name, case_name))
+        {
+            return None;
+        }
+        let path_detail = self
+            .path_skip_reason(path)
+            .map(|reason| SkipDetail {
+                reason,
+                folder: None,
+            })
+            .or_else(|| self.extension_skip_detail(&dir_names, path))
+            .or_else(|| self.hash_skip_detail(&dir_names, path));
+        path_detail.or_else(|| {
+            prefer_skip(
+                dir_names
+                    .iter()
+                    .filter_map(|dir_name| self.case_skip_detail(dir_name, &file_name, case_name)),
+                |detail| detail.reason.severity(),
+            )
+        })
+    }
+
+    /// Splits the cases of the file at the given path into the skipped and the kept
+    /// ones, in that order. The folder key and the file name are only computed once.
+    pub fn partition_cases(
+        &self,
+        path: &PathWrapper,
+        cases: &[String],
+    ) -> (Vec<String>, Vec<String>) {
+        let dir_names = self.folder_keys(path);
+        let is_path_skipped = self.is_path_skipped(&dir_names, path);
+        let file_name = self.match_name(path);
+        cases.iter().cloned().partition(|case_name| {
+            self.is_case_skipped(&dir_names, &file_name, is_path_skipped, case_name)
+        })
+    }
+
+    /// Reads the case names of the file at the given path and returns the skipped
+    /// ones, including all the cases of a skipped file.
+    pub fn skipped_cases(&self, path: &PathWrapper) -> Result<Vec<String>, eyre::Error> {
+        let cases = path.read_case_names()?;
+        Ok(self.partition_cases(path, &cases).0)
+    }
+
+    /// Checks if any of the cases of the file at the given path is neither skipped
+    /// nor excluded by the `only` mapping, stopping at the first such case.
+    pub fn has_runnable_case(&self, path: &PathWrapper, cases: &[String]) -> bool {
+        let dir_names = self.folder_keys(path);
+        let is_path_skipped = self.is_path_skipped(&dir_names, path);
+        let file_name = self.match_name(path);
+        cases.iter().any(|case_name| {
+            !self.is_case_skipped(&dir_names, &file_name, is_path_skipped, case_name)
+                && self.is_included(path, Some(case_name))
+        })
+    }
+
+    /// Checks if the case is skipped, given the keys of the folders applying to the
+    /// file, the name of the file and whether the whole path is skipped.
+    fn is_case_skipped(
+        &self,
+        dir_names: &[Folder],
+        file_name: &str,
+        is_path_skipped: bool,
+        case_name: &str,
+    ) -> bool {
+        let case_name = Some(case_name);
+        !dir_names
+            .iter()
+            .any(|dir_name| self.is_kept(dir_name, file_name, case_name))
+            && (is_path_skipped
+                || dir_names.iter().any(|dir_name| {
+                    self.case_skip_detail(dir_name, file_name, case_name)
+                        .map_or(false, |detail| detail.reason.severity().is_skip())
+                }))
+    }
+
+    /// Checks if the file or the case is listed in the `keep` mapping.
+    fn is_kept(&self, dir_name: &str, file_name: &str, case_name: Option<&str>) -> bool {
+        self.matching_folders(&self.keep, dir_name)
+            .flat_map(|(_, entries)| entries)
+            .any(|entry| {
+                self.names_match(entry.pattern(), file_name)
+                    || case_name.map_or(false, |case| self.names_match(entry.pattern(), case))
+            })
+    }
+
+    /// Checks if all the cases of the file at the given path are skipped, either by
+    /// `skip_forks` or by the `extension` or `hash` mappings.
+    fn is_path_skipped(&self, dir_names: &[Folder], path: &PathWrapper) -> bool {
+        let is_skip = |detail: SkipDetail| detail.reason.severity().is_skip();
+        self.path_skip_reason(path).is_some()
+            || self
+                .extension_skip_detail(dir_names, path)
+                .map_or(false, is_skip)
+            || self
+                .hash_skip_detail(dir_names, path)
+                .map_or(false, is_skip)
+    }
+
+    /// Returns the reason for which the file at the given path is skipped by the
+    /// `hash` mapping along with the folder key of the matching entry, if any. The
+    /// file is only read if entries apply to its folder.
+    fn hash_skip_detail(&self, dir_names: &[Folder], path: &PathWrapper) -> Option<SkipDetail> {
+        let mut matches = dir_names
+            .iter()
+            .flat_map(|dir_name| {
+                self.matching_folders(&self.hash, dir_name)
+                    .flat_map(|(folder, entries)| entries.iter().map(move |entry| (folder, entry)))
+            })
+            .peekable();
+        matches.peek()?;
+
+        let digest = format!("{:x}", Sha256::digest(fs::read(path).ok()?));
+        prefer_skip(
+            matches
+                .filter(|(_, entry)| entry.pattern().eq_ignore_ascii_case(&digest))
+                .map(|(folder, entry)| SkipDetail {
+                    reason: SkipReason::Hash(self.resolve_entry(folder, entry)),
+                    folder: Some(folder.clone()),
+                }),
+            |detail| detail.reason.severity(),
+        )
+    }
+
+    /// Returns the reason for which the file at the given path is skipped by the
+    /// `extension` mapping along with the folder key of the matching entry, if any.
+    fn extension_skip_detail(
+        &self,
+        dir_names: &[Folder],
+        path: &PathWrapper,
+    ) -> Option<SkipDetail> {
+        let extension = path.as_ref().extension()?.to_string_lossy();
+        let matches = dir_names.iter().flat_map(|dir_name| {
+            self.matching_folders(&self.extension, dir_name)
+                .flat_map(|(folder, entries)| entries.iter().map(move |entry| (folder, entry)))
+        });
+        prefer_skip(
+            matches
+                .filter(|(_, entry)| {
+                    let pattern = entry.pattern();
+                    self.names_match(pattern.strip_prefix('.').unwrap_or(pattern), &extension)
+                })
+                .map(|(folder, entry)| SkipDetail {
+                    reason: SkipReason::Extension(self.resolve_entry(folder, entry)),
+                    folder: Some(folder.clone()),
+                }),
+            |detail| detail.reason.severity(),
+        )
+    }
+
+    /// Returns the reason for which all the files under the given path are skipped, if any.
+    fn path_skip_reason(&self, path: &PathWrapper) -> Option<SkipReason> {
+        self.skip_forks
+            .iter()
+            .find(|fork| {
+                path.as_ref()
+                    .components()
+                    .any(|component| component.as_os_str().eq_ignore_ascii_case(fork))
+            })
+            .map(|fork| SkipReason::ForkPath(fork.as_str().into()))
+    }
+
+    /// Returns the reason for which the file or the case is skipped along with the
+    /// folder key of the matching entry, if any, given the folder key and the name
+    /// of the file.
+    fn case_skip_detail(
+        &self,
+        dir_name: &str,
+        file_name: &str,
+        case_name: Option<&str>,
+    ) -> Option<SkipDetail> {
+        let matches = Category::ALL.into_iter().flat_map(|category| {
+            self.matching_folders(self.category_map(category), dir_name)
+                .flat_map(move |(folder, entries)| {
+                    entries
+                        .iter()
+                        .enumerate()
+                        .filter(move |(index, _)| {
+                            self.entry_matches(category, folder, *index, file_name, case_name)
+                        })
+                        .map(move |(_, entry)| SkipDetail {
+                            reason: SkipReason::new(category, self.resolve_entry(folder, entry)),
+                            folder: Some(folder.clone()),
+                        })
+                })
+        });
+        let fork = case_name.and_then(|case_name| {
+            let (folder, forks) = self.folder_entries(&self.fork, dir_name)?;
+            self.fork_skip_reason(folder, forks, case_name)
+                .map(|reason| SkipDetail {
+                    reason,
+                    folder: Some(folder.clone()),
+                })
+        });
+
+        prefer_skip(matches.chain(fork), |detail| detail.reason.severity())
+    }
+
+    /// Returns the reason for which the case is skipped by the forks of a folder of
+    /// the `fork` mapping, if any.
+    fn fork_skip_reason(
+        &self,
+        folder: &str,
+        forks: &BTreeMap<String, Vec<SkipEntry>>,
+        case_name: &str,
+    ) -> Option<SkipReason> {
+        let fork = parse_fork(case_name)?;
+        let (base_name, suffix) = case_name.rsplit_once('_')?;
+        let stripped_name = CASE_INDICES.replace(base_name, "");
+        forks
+            .iter()
+            .find(|(f, _)| self.names_match(f, suffix) || Fork::from(f.as_str()) == fork)
+            .and_then(|(_, tests)| {
+                prefer_skip(
+                    tests.iter().filter(|test| {
+                        self.names_match(test.pattern(), base_name)
+                            || self.names_match(test.pattern(), &stripped_name)
+                    }),
+                    |test| self.entry_severity(folder, test),
+                )
+            })
+            .map(|test| SkipReason::Fork {
+                fork: suffix.to_string(),
+                test: self.resolve_entry(folder, test),
+            })
+    }
+
+    /// Returns the severity of an entry of the given folder key, which is the
+    /// `default_severity` of the folder for a bare pattern.
+    fn entry_severity(&self, folder: &str, entry: &SkipEntry) -> Severity {
+        match entry {
+            SkipEntry::Pattern(_) => self
+                .folder_entries(&self.folders, folder)
+                .and_then(|(_, config)| config.default_severity)
+                .unwrap_or_default(),
+            SkipEntry::Detailed { severity, .. } => *severity,
+        }
+    }
+
+    /// Returns a copy of the entry of the given folder key carrying its resolved
+    /// severity, see [`FolderConfig::default_severity`].
+    fn resolve_entry(&self, folder: &str, entry: &SkipEntry) -> SkipEntry {
+        let severity = self.entry_severity(folder, entry);
+        if severity == entry.severity() {
+            return entry.clone();
+        }
+        SkipEntry::Detailed {
+            pattern: entry.pattern().to_string(),
+            reason: None,
+            expires: None,
+            issue: None,
+            severity,
+            mode: SkipMode::Omit,
+        }
+    }
+
+    /// Returns an iterator over the entries of the mappings of [`Category::ALL`],
+    /// in that order.
+    pub fn entries(&self) -> impl Iterator<Item = FilterEntryRef<'_>> {
+        Category::ALL.into_iter().flat_map(move |category| {
+            self.category_map(category)
+                .iter()
+                .flat_map(move |(folder, entries)| {
+                    entries.iter().map(move |entry| FilterEntryRef {
+                        folder,
+                        category,
+                        pattern: entry.pattern(),
+                        reason: entry.reason(),
+                        expires: entry.expires(),
+                    })
+                })
+        })
+    }
+
+    /// Returns the entries of every mapping whose expiry date is before `today`.
+    pub fn expired(&self, today: NaiveDate) -> Vec<ExpiredEntry<'_>> {
+        let entries = self.named_maps().flat_map(|(map, folders)| {
+            folders.iter().flat_map(move |(folder, entries)| {
+                entries.iter().map(move |entry| (map, folder, None, entry))
+            })
+        });
+        let fork_entries = self.fork.iter().flat_map(|(folder, forks)| {
+            forks.iter().flat_map(move |(fork, entries)| {
+                entries
+                    .iter()
+                    .map(move |entry| ("fork", folder, Some(fork.as_str()), entry))
+            })
+        });
+        entries
+            .chain(fork_entries)
+            .filter_map(|(map, folder, fork, entry)| {
+                let expires = entry.expires().filter(|expires| *expires < today)?;
+                Some(ExpiredEntry {
+                    folder,
+                    map,
+                    fork,
+                    pattern: entry.pattern(),
+                    reason: entry.reason(),
+                    expires,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the mappings from folders to entries along with their name, which
+    /// are all the mappings except the `fork` one.
+    fn named_maps(&self) -> impl Iterator<Item = (&'static str, &FilterMap)> {
+        Category::ALL
+            .into_iter()
+            .map(|category| (category.name(), self.category_map(category)))
+            .chain([
+                ("only", &self.only),
+                ("keep", &self.keep),
+                ("extension", &self.extension),
+                ("hash", &self.hash),
+            ])
+    }
+
+    /// Returns the total number of skip patterns: the entries of the mappings of
+    /// [`Category::ALL`] and of the `fork`, `extension` and `hash` mappings, plus
+    /// the forks of `skip_forks`. The `only` and `keep` mappings, which don't skip
+    /// anything, aren't counted.
+    pub fn len(&self) -> usize {
+        let forks: usize = self
+            .fork
+            .values()
+            .flat_map(BTreeMap::values)
+            .map(Vec::len)
+            .sum();
+        let path_entries: usize = self
+            .extension
+            .values()
+            .chain(self.hash.values())
+            .map(Vec::len)
+            .sum();
+        self.entries().count() + forks + self.skip_forks.len() + path_entries
+    }
+
+    /// Returns true if the filter doesn't skip anything.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the mapping of the filter for the given category.
+    fn category_map(&self, category: Category) -> &FilterMap {
+        match category {
+            Category::Filename => &self.filename,
+            Category::Regex => &self.regex,
+            Category::Glob => &self.glob,
+            Category::TestName => &self.test_name,
+            Category::TestNameRegex => &self.test_name_regex,
+            Category::Index => &self.index,
+        }
+    }
+
+    /// Checks if the entry at the given index of the folder's entries for the category
+    /// matches the file name or the case name. The `testname` and `testname_regex`
+    /// entries only ever match a case name.
+    fn entry_matches(
+        &self,
+        category: Category,
+        folder: &str,
+        index: usize,
+        file_name: &str,
+        case_name: Option<&str>,
+    ) -> bool {
+        match category {
+            Category::Filename => {
+                let pattern = self.filename[folder][index].pattern();
+                pattern == ALL_FILES || self.names_match(pattern, file_name)
+            }
+            Category::Regex => {
+                // A file matching a negated pattern is kept by the whole folder
+                let (entries, regexes) = (&self.regex[folder], &self.regex_cache()[folder]);
+                let is_kept = entries.iter().zip(regexes).any(|(entry, regex)| {
+                    negated_regex(entry.pattern()).is_some() && regex.is_match(file_name)
+                });
+                !is_kept
+                    && (negated_regex(entries[index].pattern()).is_some()
+                        || regexes[index].is_match(file_name))
+            }
+            Category::Glob => {
+                let options = MatchOptions {
+                    case_sensitive: !self.case_insensitive,
+                    ..MatchOptions::default()
+                };
+                self.glob_cache()[folder][index].matches_with(file_name, options)
+            }
+            Category::TestName => case_name.map_or(false, |case_name| {
+                let pattern = self.test_name[folder][index].pattern();
+                if pattern.contains('*') {
+                    self.wildcard_match(pattern, case_name)
+                } else {
+                    self.names_match(pattern, case_name)
+                }
+            }),
+            Category::TestNameRegex => case_name.map_or(false, |case_name| {
+                self.test_name_regex_cache()[folder][index].is_match(case_name)
+            }),
+            Category::Index => case_name
+                .and_then(parse_case_indices)
+                .zip(IndexPredicate::parse(self.index[folder][index].pattern()))
+                .map_or(false, |(indices, predicate)| predicate.matches(&indices)),
+        }
+    }
+
+    /// Returns the key of the folder containing the given path. By default, the key is
+    /// the name of the parent directory. If the filter is path scoped, the key is the
+    /// path of the parent directory relative to the `BlockchainTests` folder (or the
+    /// full parent path if absent), e.g. `GeneralStateTests/stCreate2`.
+    fn folder_key(&self, path: &PathWrapper) -> Folder {
+        let parent = path.parent();
+        if !self.path_scoped {
+            return parent.file_stem_to_string();
+        }
+        relative_path(&parent)
+    }
+
+    /// Returns the string the file entries are matched against for the given path,
+    /// according to the [match target](MatchTarget).
+    fn match_name(&self, path: &PathWrapper) -> String {
+        match self.match_target {
+            MatchTarget::Stem => path.file_stem_to_string(),
+            MatchTarget::FileName => path
+                .as_ref()
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            MatchTarget::RelativePath => relative_path(path),
+        }
+    }
+
+    /// Returns the keys of the folders whose entries apply to the given path: the
+    /// [folder key](Self::folder_key) of the path, followed by the keys of its ancestor
+    /// directories, from the closest to the furthest, if the filter cascades.
+    fn folder_keys(&self, path: &PathWrapper) -> Vec<Folder> {
+        let folder_key = self.folder_key(path);
+        if !self.cascade {
+            return vec![folder_key];
+        }
+
+        if self.path_scoped {
+            let mut keys = vec![folder_key.clone()];
+            let mut key = folder_key.as_str();
+            while let Some((parent, _)) = key.rsplit_once('/') {
+                keys.push(parent.to_string());
+                key = parent;
+            }
+            return keys;
+        }
+
+        let parent = path.parent();
+        std::iter::once(folder_key)
+            .chain(
+                parent
+                    .as_ref()
+                    .ancestors()
+                    .skip(1)
+                    .filter_map(Path::file_stem)
+                    .map(|stem| stem.to_string_lossy().into_owned()),
+            )
+            .collect()
+    }
+
+    /// Returns the folder key and the entries of the map for the given directory.
+    /// The case of the directory is ignored if the filter is case insensitive.
+    fn folder_entries<'a, V>(
+        &self,
+        map: &'a BTreeMap<Folder, V>,
+        dir_name: &str,
+    ) -> Option<(&'a Folder, &'a V)> {
+        if self.case_insensitive {
+            map.iter()
+                .find(|(folder, _)| folder.eq_ignore_ascii_case(dir_name))
+        } else {
+            map.get_key_value(dir_name)
+        }
+    }
+
+    /// Checks, without allocating, whether any entry may apply to the given path.
+    /// Returns false only if no mapping has the directory of the path or the `*`
+    /// folder as key, in which case the path can't be skipped.
+    fn may_apply(&self, path: &PathWrapper) -> bool {
+        if self.path_scoped || self.cascade || !self.skip_forks.is_empty() {
+            return true;
+        }
+        let Some(dir_name) = path
+            .as_ref()
+            .parent()
+            .and_then(Path::file_stem)
+            .and_then(|dir_name| dir_name.to_str())
+        else {
+            return true;
+        };
+        Category::ALL
+            .into_iter()
+            .map(|category| self.category_map(category))
+            .chain([&self.extension, &self.hash])
+            .any(|map| self.matching_folders(map, dir_name).next().is_some())
+            || self.matching_folders(&self.fork, dir_name).next().is_some()
+    }
+
+    /// Returns the folder keys and the entries of the map applying to the given
+    /// directory: the ones of the directory itself, followed by the ones of the
+    /// `*` wildcard folder.
+    fn matching_folders<'a, V>(
+        &self,
+        map: &'a BTreeMap<Folder, V>,
+        dir_name: &str,
+    ) -> impl Iterator<Item = (&'a Folder, &'a V)> {
+        let wildcard = (dir_name != WILDCARD_FOLDER)
+            .then(|| map.get_key_value(WILDCARD_FOLDER))
+            .flatten();
+        self.folder_entries(map, dir_name)
+            .into_iter()
+            .chain(wildcard)
+    }
+
+    /// Compares a filter entry with a file or case name, respecting the case
+    /// sensitivity of the filter.
+    fn names_match(&self, entry: &str, name: &str) -> bool {
+        if self.case_insensitive {
+            entry.eq_ignore_ascii_case(name)
+        } else {
+            entry == name
+        }
+    }
+
+    /// Checks if the name matches the entry, where each `*` of the entry matches any
+    /// sequence of characters and every other character is matched literally.
+    fn wildcard_match(&self, entry: &str, name: &str) -> bool {
+        let pattern = Pattern::escape(entry).replace("[*]", "*");
+        let options = MatchOptions {
+            case_sensitive: !self.case_insensitive,
+            ..MatchOptions::default()
+        };
+        Pattern::new(&pattern).map_or(false, |pattern| pattern.matches_with(name, options))
+    }
+
+    /// Compiles the regex pattern of the folder with the engine of the filter,
+    /// respecting its case sensitivity.
+    fn compile_regex(&self, folder: &str, pattern: &str) -> Result<CompiledRegex, FilterError> {
+        let full_pattern = if self.is_full_match() {
+            format!("^(?:{pattern})$")
+        } else {
+            pattern.to_string()
+        };
+        match self.regex_engine {
+            RegexEngine::Default => RegexBuilder::new(&full_pattern)
+                .case_insensitive(self.case_insensitive)
+                .build()
+                .map(CompiledRegex::Default)
+                .map_err(|error| FilterError::InvalidRegex {
+                    folder: folder.to_string(),
+                    pattern: pattern.to_string(),
+                    error,
+                }),
+            #[cfg(feature = "fancy-regex")]
+            RegexEngine::Fancy => {
+                let flags = if self.case_insensitive { "(?i)" } else { "" };
+                fancy_regex::Regex::new(&format!("{flags}{full_pattern}"))
+                    .map(|regex| CompiledRegex::Fancy(Box::new(regex)))
+                    .map_err(|error| FilterError::InvalidFancyRegex {
+                        folder: folder.to_string(),
+                        pattern: pattern.to_string(),
+                        error: Box::new(error),
+                    })
+            }
+            #[cfg(not(feature = "fancy-regex"))]
+            RegexEngine::Fancy => Err(FilterError::FancyRegexUnavailable),
+        }
+    }
+
+    /// Returns true if the regex patterns should match the whole name.
+    fn is_full_match(&self) -> bool {
+        self.full_match.unwrap_or(true)
+    }
+
+    /// Warns about the regex patterns which look like they rely on substring matching,
+    /// when the filter doesn't explicitly choose a matching mode: patterns containing
+    /// regex syntax which aren't anchored at both ends, either by `^`/`$` or by `.*`.
+    fn warn_substring_patterns(&self) {
+        if self.full_match.is_some() {
+            return;
+        }
+        let regexes = self.regex.iter().chain(&self.test_name_regex);
+        for (folder, entries) in regexes {
+            for entry in entries {
+                let pattern = entry.pattern();
+                let is_literal = regex::escape(pattern) == pattern;
+                let is_anchored = (pattern.starts_with('^') || pattern.starts_with(".*"))
+                    && (pattern.ends_with('$') || pattern.ends_with(".*"));
+                if !is_literal && !is_anchored {
+                    log::warn!(
+                        "Regex pattern `{pattern}` in folder {folder} now matches the whole name, set `full_match: false` to restore substring matching"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Returns the compiled `regex` patterns, compiling them on first use.
+    fn regex_cache(&self) -> &RegexMap {
+        self.regex_cache
+            .get_or_init(|| self.compile_regex_map(&self.regex, true))
+    }
+
+    /// Returns the compiled `testname_regex` patterns, compiling them on first use.
+    fn test_name_regex_cache(&self) -> &RegexMap {
+        self.test_name_regex_cache
+            .get_or_init(|| self.compile_regex_map(&self.test_name_regex, false))
+    }
+
+    /// Compiles all the regex patterns of the mapping, without the leading `!` of
+    /// the negated patterns if `negatable` is set.
+    fn compile_regex_map(&self, map: &FilterMap, negatable: bool) -> RegexMap {
+        map.iter()
+            .map(|(folder, patterns)| {
+                let regexes = patterns
+                    .iter()
+                    .map(|pattern| {
+                        let pattern = pattern.pattern();
+                        let pattern = negated_regex(pattern)
+                            .filter(|_| negatable)
+                            .unwrap_or(pattern);
+                        self.compile_regex(folder, pattern).unwrap_or_else(|err| {
+                            panic!("Error with regex pattern{}: {err}", self.source_context())
+                        })
+                    })
+                    .collect();
+                (folder.clone(), regexes)
+            })
+            .collect()
+    }
+
+    /// Returns the compiled glob patterns, compiling them on first use.
+    fn glob_cache(&self) -> &GlobMap {
+        self.glob_cache.get_or_init(|| {
+            self.glob
+                .iter()
+                .map(|(folder, patterns)| {
+                    let globs = patterns
+                        .iter()
+                        .map(|pattern| {
+                            Pattern::new(pattern.pattern()).unwrap_or_else(|error| {
+                                let err = FilterError::InvalidGlob {
+                                    folder: folder.clone(),
+                                    pattern: pattern.pattern().to_string(),
+                                    error,
+                                };
+                                panic!("Error with glob pattern{}: {err}", self.source_context())
+                            })
+                        })
+                        .collect();
+                    (folder.clone(), globs)
+                })
+                .collect()
+        })
+    }
+
+    /// Returns the ` in filter file <path>` suffix naming the source of the filter in
+    /// error messages, or an empty string if the filter wasn't loaded from a file.
+    fn source_context(&self) -> String {
+        self.source
+            .as_ref()
+            .map(|source| format!(" in filter file {}", source.display()))
+            .unwrap_or_default()
+    }
+
+    /// Checks if the given path is included by the `only` mapping. If the mapping
+    /// is empty, every path is included. Otherwise, the path is included if its
+    /// directory is a key of the mapping and either the file name or the case name
+    /// is listed under it.
+    pub fn is_included(&self, path: &PathWrapper, case_name: Option<&str>) -> bool {
+        if self.only.is_empty() {
+            return true;
+        }
+
+        let dir_name = self.folder_key(path);
+        let file_name = self.match_name(path);
+
+        self.folder_entries(&self.only, &dir_name)
+            .map(|(_, included)| {
+                included.iter().any(|entry| {
+                    self.names_match(entry.pattern(), &file_name)
+                        || case_name.map_or(false, |case| self.names_match(entry.pattern(), case))
+                })
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Returns the path relative to the `BlockchainTests` folder, or the full path if
+/// absent, with `/` separated components.
+fn relative_path(path: &PathWrapper) -> String {
+    path.components_after(BLOCKCHAIN_TESTS)
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Returns the pattern of a negated `regex` entry without its leading `!`, or `None`
+/// if the entry isn't negated.
+fn negated_regex(pattern: &str) -> Option<&str> {
+    pattern.strip_prefix('!')
+}
+
+/// Returns the first item with the `skip` severity, or the first item if none has it.
+fn prefer_skip<T>(
+    mut items: impl Iterator<Item = T>,
+    severity: impl Fn(&T) -> Severity,
+) -> Option<T> {
+    let first = items.next()?;
+    if severity(&first).is_skip() {
+        return Some(first);
+    }
+    Some(items.find(|item| severity(item).is_skip()).unwrap_or(first))
+}
+
+/// Replaces the `${VAR}` placeholders with the value given by `lookup`. Returns
+/// `None` if a variable is unset or if the pattern ends up empty.
+fn expand_env_placeholders(
+    pattern: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Option<String> {
+    let mut expanded = String::with_capacity(pattern.len());
+    let mut last = 0;
+    for captures in ENV_PLACEHOLDER.captures_iter(pattern) {
+        let placeholder = captures.get(0)?;
+        expanded.push_str(&pattern[last..placeholder.start()]);
+        expanded.push_str(&lookup(&captures[1])?);
+        last = placeholder.end();
+    }
+    expanded.push_str(&pattern[last..]);
+    (!expanded.is_empty()).then_some(expanded)
+}
+
+/// Appends the entries of `rhs` to the entries of `lhs`, skipping
+/// entries with a pattern already present in `lhs`. The entries of a key present
+/// in both mappings are combined according to the policy.
+fn merge_map(
+    lhs: &mut BTreeMap<String, Vec<SkipEntry>>,
+    rhs: BTreeMap<String, Vec<SkipEntry>>,
+    policy: MergePolicy,
+) {
+    for (key, entries) in rhs {
+        let lhs_entries = lhs.entry(key).or_default();
+        match policy {
+            MergePolicy::PreferSelf if !lhs_entries.is_empty() => continue,
+            MergePolicy::PreferOther => lhs_entries.clear(),
+            _ => {}
+        }
+        for entry in entries {
+            if !lhs_entries.iter().any(|e| e.pattern() == entry.pattern()) {
+                lhs_entries.push(entry);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    #[ignore]
+    fn test_filter_file() {
+        let filter = Filter::load_file("../../blockchain-tests-skip.yml").unwrap();
+        let path = PathWrapper::from(Path::new(
+            "../../ef-testing/ethereum-tests/BlockchainTests/GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json",
+        ).to_path_buf());
+        assert!(filter.is_skipped(&path, None));
+    }
+
+    #[test]
+    fn test_filter_regex() {
+        let filter = Filter::load_file("../../blockchain-tests-skip.yml").unwrap();
+        let path = PathWrapper::from(Path::new(
+            "../../ef-testing/ethereum-tests/BlockchainTests/GeneralStateTests/stBadOpcode/opc4DDiffPlaces.json",
+        ).to_path_buf());
+        assert!(filter.is_skipped(&path, None));
+    }
+
+    #[test]
+    #[ignore]
+    fn test_filter_test() {
+        let filter = Filter::load_file("../../blockchain-tests-skip.yml").unwrap();
+        let path = PathWrapper::from(Path::new(
+            "../../ef-testing/ethereum-tests/BlockchainTests/GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json",
+        ).to_path_buf());
+        assert!(filter.is_skipped(&path, Some("Opcodes_TransactionInit_d111g0v0_Shanghai")));
+    }
+
+    #[test]
+    fn test_validate_invalid_regex() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex:
+              stBadOpcode:
+                - ^opc.*
+                - "[unclosed"
+            testname: {}
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let err = filter.validate().unwrap_err();
+
+        // Then
+        match err {
+            FilterError::InvalidRegex {
+                folder, pattern, ..
+            } => {
+                assert_eq!(folder, "stBadOpcode");
+                assert_eq!(pattern, "[unclosed");
+            }
+            _ => panic!("expected an invalid regex error, got {err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_filter_full_match() {
+        // Given
+        let yaml = r#"
+            filename: {}
+            regex:
+              stFoo:
+                - stFoo
+            testname: {}
+            "#;
+        let anchored: Filter = serde_yaml::from_str(yaml).unwrap();
+        let substring: Filter = serde_yaml::from_str(&format!("{yaml}full_match: false")).unwrap();
+        let path = |name: &str| PathWrapper::from(Path::new("GeneralStateTests/stFoo").join(name));
+
+        // Then
+        assert!(anchored.is_skipped(&path("stFoo.json"), None));
+        assert!(!anchored.is_skipped(&path("stFooBar.json"), None));
+        assert!(substring.is_skipped(&path("stFoo.json"), None));
+        assert!(substring.is_skipped(&path("stFooBar.json"), None));
+    }
+
+    #[test]
+    fn test_filter_glob() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex: {}
+            testname: {}
+            glob:
+              stCallCreateCallCodeTest:
+                - Call*
+              stRandom:
+                - randomStatetest?
+                - randomStatetest1[01]
+            "#,
+        )
+        .unwrap();
+        let call = PathWrapper::from(
+            Path::new("GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json")
+                .to_path_buf(),
+        );
+        let not_call = PathWrapper::from(
+            Path::new("GeneralStateTests/stCallCreateCallCodeTest/callcall_00.json").to_path_buf(),
+        );
+        let single_char = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest7.json").to_path_buf(),
+        );
+        let class = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest11.json").to_path_buf(),
+        );
+        let not_class = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest12.json").to_path_buf(),
+        );
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(&call, None),
+            Some(SkipReason::Glob("Call*".into()))
+        );
+        assert!(!filter.is_skipped(&not_call, None));
+        assert!(filter.is_skipped(&single_char, None));
+        assert!(filter.is_skipped(&class, None));
+        assert!(!filter.is_skipped(&not_class, None));
+    }
+
+    #[test]
+    fn test_load_file_invalid_regex() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        writeln!(
+            file,
+            "filename: {{}}\nregex:\n  stBadOpcode:\n    - \"[unclosed\"\ntestname: {{}}"
+        )
+        .unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        let err = Filter::load_file(path).unwrap_err();
+
+        // Then
+        let message = err.to_string();
+        assert!(message.contains(path));
+        assert!(message.contains("[unclosed"));
+        assert!(message.contains("stBadOpcode"));
+    }
+
+    #[test]
+    // The compiled patterns are interior mutable but are not part of the hash
+    #[allow(clippy::mutable_key_type)]
+    fn test_filter_hash_map_key() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stRandom:
+                - randomStatetest0
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest0.json").to_path_buf(),
+        );
+        assert!(filter.is_skipped(&path, None));
+        let mut cache = std::collections::HashMap::new();
+
+        // When
+        cache.insert(filter.clone(), 1);
+
+        // Then
+        let clone = filter.clone();
+        assert_eq!(clone, filter);
+        assert_eq!(cache.get(&clone), Some(&1));
+        assert_eq!(cache.get(&Filter::default()), None);
+    }
+
+    #[test]
+    fn test_load_file_cached() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        writeln!(file, "filename: {{}}\nregex: {{}}\ntestname: {{}}").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        let first = Filter::load_file_cached(path).unwrap();
+        let second = Filter::load_file_cached(path).unwrap();
+
+        // Then
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_load_file_without_version() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        writeln!(
+            file,
+            "filename: {{}}\nregex: {{}}\ntest_name:\n  stTransactionTest:\n    - Opcodes_TransactionInit_d1g0v0_Shanghai"
+        )
+        .unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        let filter = Filter::load_file(path).unwrap();
+
+        // Then
+        assert_eq!(filter.version, None);
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json")
+                .to_path_buf(),
+        );
+        assert!(filter.is_skipped(&path, Some("Opcodes_TransactionInit_d1g0v0_Shanghai")));
+    }
+
+    #[test]
+    fn test_load_file_unsupported_version() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        writeln!(
+            file,
+            "version: 2\nfilename: {{}}\nregex: {{}}\ntestname: {{}}"
+        )
+        .unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        let err = Filter::load_file(path).unwrap_err();
+
+        // Then
+        let message = err.to_string();
+        assert!(message.contains(path));
+        assert!(message.contains("unsupported filter version 2"));
+    }
+
+    #[test]
+    fn test_load_file_strict_duplicate() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        writeln!(
+            file,
+            "filename:\n  stCallCreateCallCodeTest:\n    - Call1024PreCalls\n    - Call1024PreCalls\nregex: {{}}\ntestname: {{}}"
+        )
+        .unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        let err = Filter::load_file_strict(path).unwrap_err();
+
+        // Then
+        assert!(err.to_string().contains(path));
+        let FilterError::Validation { error, .. } = err else {
+            panic!("expected a validation error, got {err:?}");
+        };
+        assert!(matches!(
+            *error,
+            FilterError::Duplicate { ref folder, ref pattern, map: "filename" }
+                if folder == "stCallCreateCallCodeTest" && pattern == "Call1024PreCalls"
+        ));
+        assert!(Filter::load_file(path).is_ok());
+    }
+
+    #[test]
+    fn test_check_duplicates_all_mappings() {
+        // Given
+        let filters = [
+            ("keep", "keep:\n  stRandom:\n    - a\n    - a", "a"),
+            (
+                "extension",
+                "extension:\n  stRandom:\n    - yml\n    - yml",
+                "yml",
+            ),
+            (
+                "fork",
+                "fork:\n  stRandom:\n    Cancun:\n      - x\n      - x",
+                "Cancun/x",
+            ),
+        ];
+
+        for (map, content, duplicated) in filters {
+            let filter: Filter = serde_yaml::from_str(content).unwrap();
+
+            // When
+            let err = filter.check_duplicates().unwrap_err();
+
+            // Then
+            assert!(
+                matches!(
+                    err,
+                    FilterError::Duplicate { ref folder, ref pattern, map: err_map }
+                        if folder == "stRandom" && pattern == duplicated && err_map == map
+                ),
+                "{map}: {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_file_toml() {
+        // Given
+        let yaml = r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stRandom:
+                - randomStatetest[0-4]
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d0g0v0_Shanghai
+            "#;
+        let toml = r#"
+            [filename]
+            stCallCreateCallCodeTest = ["Call1024PreCalls"]
+
+            [regex]
+            stRandom = ["randomStatetest[0-4]"]
+
+            [testname]
+            stTransactionTest = ["Opcodes_TransactionInit_d0g0v0_Shanghai"]
+            "#;
+        let dir = tempfile::tempdir().unwrap();
+        let yaml_path = dir.path().join("filter.yml");
+        let toml_path = dir.path().join("filter.toml");
+        fs::write(&yaml_path, yaml).unwrap();
+        fs::write(&toml_path, toml).unwrap();
+
+        // When
+        let yaml_filter = Filter::load_file(yaml_path.to_str().unwrap()).unwrap();
+        let toml_filter = Filter::load_file(toml_path.to_str().unwrap()).unwrap();
+
+        // Then
+        for (path, case_name) in [
+            ("stCallCreateCallCodeTest/Call1024PreCalls.json", None),
+            (
+                "stCallCreateCallCodeTest/Callcode1024BalanceTooLow.json",
+                None,
+            ),
+            ("stRandom/randomStatetest0.json", None),
+            ("stRandom/randomStatetest5.json", None),
+            (
+                "stTransactionTest/Opcodes_TransactionInit.json",
+                Some("Opcodes_TransactionInit_d0g0v0_Shanghai"),
+            ),
+            (
+                "stTransactionTest/Opcodes_TransactionInit.json",
+                Some("Opcodes_TransactionInit_d1g0v0_Shanghai"),
+            ),
+        ] {
+            let path = PathWrapper::from(Path::new("GeneralStateTests").join(path));
+            assert_eq!(
+                yaml_filter.is_skipped(&path, case_name),
+                toml_filter.is_skipped(&path, case_name)
+            );
+        }
+        assert!(toml_filter.is_skipped(
+            &PathWrapper::from(
+                Path::new("GeneralStateTests/stRandom/randomStatetest0.json").to_path_buf()
+            ),
+            None
+        ));
+    }
+
+    #[test]
+    fn test_load_errors() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let invalid_regex = "filename: {}\nregex:\n  stBadOpcode: ['[unclosed']\ntestname: {}";
+        let path = dir.path().join("filter.yml");
+        fs::write(&path, invalid_regex).unwrap();
+        let path = path.to_str().unwrap();
+        let missing = dir.path().join("missing.yml");
+        let load_eyre = || -> Result<Filter, eyre::Error> { Ok(Filter::load_file(path)?) };
+
+        // Then
+        assert!(matches!(
+            Filter::load_file(missing.to_str().unwrap()),
+            Err(FilterError::Io { path, .. }) if path == missing
+        ));
+        assert!(matches!(
+            Filter::from_yaml_str("filename: [not a mapping]"),
+            Err(FilterError::Parse { path: None, .. })
+        ));
+        assert!(matches!(
+            Filter::from_yaml_str(invalid_regex),
+            Err(FilterError::InvalidRegex { pattern, .. }) if pattern == "[unclosed"
+        ));
+        assert!(matches!(
+            Filter::load_file(path),
+            Err(FilterError::Validation { error, .. })
+                if matches!(*error, FilterError::InvalidRegex { .. })
+        ));
+        assert!(load_eyre()
+            .unwrap_err()
+            .downcast_ref::<FilterError>()
+            .is_some());
+    }
+
+    #[test]
+    fn test_load_file_unknown_extension() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("filter.json");
+        fs::write(&path, "{}").unwrap();
+        let path = path.to_str().unwrap();
+
+        // When
+        let err = Filter::load_file(path).unwrap_err();
+
+        // Then
+        assert!(err.to_string().contains("Unsupported filter file"));
+        assert!(err.to_string().contains(path));
+    }
+
+    #[test]
+    fn test_load_or_default_missing_file() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.yml");
+
+        // When
+        let filter = Filter::load_or_default(path.to_str().unwrap()).unwrap();
+
+        // Then
+        assert_eq!(filter.diff(&Filter::embedded()), Some(vec![]));
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stEIP3855-push0/push0.json").to_path_buf(),
+        );
+        assert!(filter.is_skipped(&path, None));
+    }
+
+    #[test]
+    fn test_load_from_env() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nightly.yml");
+        fs::write(
+            &path,
+            "filename:\n  stRandom:\n    - randomStatetest0\nregex: {}\ntestname: {}",
+        )
+        .unwrap();
+        let lookup = |key: &str| (key == SKIP_FILE_ENV).then(|| path.to_str().unwrap().to_string());
+
+        // When
+        let filter = Filter::load_from_env_with("../../blockchain-tests-skip.yml", lookup);
+
+        // Then
+        let filter = filter.unwrap();
+        assert_eq!(filter.filename.len(), 1);
+        assert_eq!(
+            filter.filename["stRandom"],
+            vec![SkipEntry::from("randomStatetest0")]
+        );
+        assert!(filter.test_name.is_empty());
+    }
+
+    #[test]
+    fn test_load_file_expands_env() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("filter.yml");
+        fs::write(
+            &path,
+            "filename:\n  stRandom:\n    - ${EF_TESTS_UNSET_VAR}\nregex: {}\ntestname: {}",
+        )
+        .unwrap();
+
+        // When
+        let filter = Filter::load_file(path.to_str().unwrap()).unwrap();
+
+        // Then
+        assert!(filter.filename["stRandom"].is_empty());
+    }
+
+    #[test]
+    fn test_expand_env() {
+        // Given
+        let mut filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - ${EF_TESTS_UNSET_VAR}
+            regex:
+              stRandom:
+                - randomStatetest${EF_TESTS_TEST_INDEX}
+                - ${EF_TESTS_UNSET_VAR}.*
+            skip_forks:
+                - ${EF_TESTS_UNSET_VAR}Cancun
+            ",
+        )
+        .unwrap();
+
+        // When
+        filter.expand_env(&|key| (key == "EF_TESTS_TEST_INDEX").then(|| "1".to_string()));
+
+        // Then
+        let path =
+            |name: &str| PathWrapper::from(Path::new("GeneralStateTests/stRandom").join(name));
+        assert!(filter.is_skipped(&path("randomStatetest1.json"), None));
+        assert!(!filter.is_skipped(&path("randomStatetest0.json"), None));
+        assert!(filter.filename["stRandom"].is_empty());
+        assert_eq!(filter.regex["stRandom"].len(), 1);
+        assert!(filter.skip_forks.is_empty());
+    }
+
+    #[test]
+    fn test_regex_cache() {
+        // Given
+        let filter = Filter::load_file("../../blockchain-tests-skip.yml").unwrap();
+
+        // When
+        let cache = filter.regex_cache();
+
+        // Then
+        assert_eq!(cache.len(), filter.regex.len());
+        for (folder, patterns) in &filter.regex {
+            assert_eq!(cache[folder].len(), patterns.len());
+        }
+        assert!(std::ptr::eq(cache, filter.regex_cache()));
+    }
+
+    #[test]
+    fn test_skip_reason() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stBadOpcode:
+                - ^opc.*
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d111g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+        let filename = PathWrapper::from(
+            Path::new("GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json")
+                .to_path_buf(),
+        );
+        let regex = PathWrapper::from(
+            Path::new("GeneralStateTests/stBadOpcode/opc4DDiffPlaces.json").to_path_buf(),
+        );
+        let test_name = PathWrapper::from(
+            Path::new("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json")
+                .to_path_buf(),
+        );
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(&filename, None),
+            Some(SkipReason::Filename("Call1024PreCalls".into()))
+        );
+        assert_eq!(
+            filter.skip_reason(&regex, None),
+            Some(SkipReason::Regex("^opc.*".into()))
+        );
+        assert_eq!(
+            filter.skip_reason(
+                &test_name,
+                Some("Opcodes_TransactionInit_d111g0v0_Shanghai")
+            ),
+            Some(SkipReason::TestName(
+                "Opcodes_TransactionInit_d111g0v0_Shanghai".into()
+            ))
+        );
+        assert_eq!(
+            filter.skip_reason(&test_name, Some("Opcodes_TransactionInit_d0g0v0_Shanghai")),
+            None
+        );
+        assert_eq!(filter.skip_reason(&test_name, None), None);
+    }
+
+    #[test]
+    fn test_skip_entry_reason() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+                - pattern: Callcode1024BalanceTooLow
+                  reason: "flaky gas accounting #123"
+            regex: {}
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let bare = PathWrapper::from(
+            Path::new("GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json")
+                .to_path_buf(),
+        );
+        let detailed = PathWrapper::from(
+            Path::new("GeneralStateTests/stCallCreateCallCodeTest/Callcode1024BalanceTooLow.json")
+                .to_path_buf(),
+        );
+
+        // When
+        let bare_reason = filter.skip_reason(&bare, None).unwrap();
+        let detailed_reason = filter.skip_reason(&detailed, None).unwrap();
+
+        // Then
+        assert_eq!(bare_reason.reason(), None);
+        assert_eq!(detailed_reason.reason(), Some("flaky gas accounting #123"));
+        assert_eq!(
+            detailed_reason.entry().pattern(),
+            "Callcode1024BalanceTooLow"
+        );
+        let serialized = serde_yaml::to_string(&filter).unwrap();
+        assert!(serialized.contains("- Call1024PreCalls\n"));
+        assert!(serialized.contains("pattern: Callcode1024BalanceTooLow"));
+    }
+
+    #[test]
+    fn test_filter_test_name_regex() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex: {}
+            testname: {}
+            testname_regex:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d\d+g0v0_.*
+            ",
+        )
+        .unwrap();
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json")
+                .to_path_buf(),
+        );
+
+        // Then
+        for case_name in [
+            "Opcodes_TransactionInit_d0g0v0_Shanghai",
+            "Opcodes_TransactionInit_d111g0v0_Shanghai",
+            "Opcodes_TransactionInit_d12g0v0_Cancun",
+        ] {
+            assert_eq!(
+                filter.skip_reason(&path, Some(case_name)),
+                Some(SkipReason::TestNameRegex(
+                    "Opcodes_TransactionInit_d\\d+g0v0_.*".into()
+                ))
+            );
+        }
+        assert!(!filter.is_skipped(&path, Some("Opcodes_TransactionInit_d0g1v0_Shanghai")));
+        assert!(!filter.is_skipped(&path, None));
+    }
+
+    #[test]
+    fn test_filter_fork() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex: {}
+            testname: {}
+            fork:
+              stTransactionTest:
+                Shanghai:
+                  - Opcodes_TransactionInit
+                Cancun:
+                  - Opcodes_TransactionInit_d0g0v0
+            "#,
+        )
+        .unwrap();
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json")
+                .to_path_buf(),
+        );
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(&path, Some("Opcodes_TransactionInit_d111g0v0_Shanghai")),
+            Some(SkipReason::Fork {
+                fork: "Shanghai".to_string(),
+                test: "Opcodes_TransactionInit".into()
+            })
+        );
+        assert!(!filter.is_skipped(&path, Some("Opcodes_TransactionInit_d111g0v0_Cancun")));
+        assert!(filter.is_skipped(&path, Some("Opcodes_TransactionInit_d0g0v0_Cancun")));
+        assert!(!filter.is_skipped(&path, Some("Opcodes_TransactionInit_d111g0v0_Prague")));
+        assert!(!filter.is_skipped(&path, None));
+    }
+
+    #[test]
+    fn test_filter_fork_alias() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            fork:
+              stTransactionTest:
+                Merge:
+                  - Opcodes_TransactionInit
+                Osaka:
+                  - Opcodes_TransactionInit
+            ",
+        )
+        .unwrap();
+        let path = PathWrapper::from(PathBuf::from(
+            "GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json",
+        ));
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(&path, Some("Opcodes_TransactionInit_d0g0v0_Paris")),
+            Some(SkipReason::Fork {
+                fork: "Paris".to_string(),
+                test: "Opcodes_TransactionInit".into()
+            })
+        );
+        assert!(filter.is_skipped(&path, Some("Opcodes_TransactionInit_d0g0v0_Merge")));
+        assert!(filter.is_skipped(&path, Some("Opcodes_TransactionInit_d0g0v0_Osaka")));
+        assert!(!filter.is_skipped(&path, Some("Opcodes_TransactionInit_d0g0v0_Shanghai")));
+    }
+
+    #[test]
+    fn test_skipped_cases() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+            regex: {}
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d0g0v0_Shanghai
+            ",
+        )
+        .unwrap();
+        let path = |name: &str| {
+            PathWrapper::from(
+                Path::new("src/test_data/BlockchainTests/GeneralStateTests").join(name),
+            )
+        };
+        let file = path("stRandom/randomStatetest0.json");
+
+        // When
+        let skipped = filter
+            .skipped_cases(&path("stTransactionTest/Opcodes_TransactionInit.json"))
+            .unwrap();
+        let skipped_file = filter.skipped_cases(&file).unwrap();
+
+        // Then
+        assert_eq!(skipped, vec!["Opcodes_TransactionInit_d0g0v0_Shanghai"]);
+        assert_eq!(skipped_file, file.read_case_names().unwrap());
+        assert!(!skipped_file.is_empty());
+        assert!(filter
+            .skipped_cases(&path("stRandom/missing.json"))
+            .is_err());
+    }
+
+    #[test]
+    fn test_partition_cases() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex: {}
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d0g0v0_Shanghai
+            testname_regex:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d\d+g0v0_Cancun
+            ",
+        )
+        .unwrap();
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json")
+                .to_path_buf(),
+        );
+        let cases = [
+            "Opcodes_TransactionInit_d0g0v0_Cancun",
+            "Opcodes_TransactionInit_d0g0v0_Shanghai",
+            "Opcodes_TransactionInit_d1g0v0_Shanghai",
+            "Opcodes_TransactionInit_d2g0v0_Cancun",
+        ]
+        .map(String::from);
+
+        // When
+        let (skipped, kept) = filter.partition_cases(&path, &cases);
+
+        // Then
+        assert_eq!(
+            skipped,
+            vec![
+                "Opcodes_TransactionInit_d0g0v0_Cancun",
+                "Opcodes_TransactionInit_d0g0v0_Shanghai",
+                "Opcodes_TransactionInit_d2g0v0_Cancun",
+            ]
+        );
+        assert_eq!(kept, vec!["Opcodes_TransactionInit_d1g0v0_Shanghai"]);
+    }
+
+    #[test]
+    fn test_filter_wildcard_folder() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex:
+              "*":
+                - .*_benchmark
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let path = |path: &str| PathWrapper::from(Path::new(path).to_path_buf());
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(
+                &path("GeneralStateTests/stRandom/random_benchmark.json"),
+                None
+            ),
+            Some(SkipReason::Regex(".*_benchmark".into()))
+        );
+        assert!(filter.is_skipped(
+            &path("GeneralStateTests/stBadOpcode/opcodes_benchmark.json"),
+            None
+        ));
+        assert!(!filter.is_skipped(
+            &path("GeneralStateTests/stRandom/randomStatetest0.json"),
+            None
+        ));
+    }
+
+    #[cfg(feature = "logging")]
+    #[test]
+    #[tracing_test::traced_test]
+    fn test_is_skipped_logging() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest0
+            regex: {}
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest0.json").to_path_buf(),
+        );
+
+        // When
+        assert!(filter.is_skipped(&path, None));
+
+        // Then
+        logs_assert(|lines: &[&str]| {
+            let events: Vec<_> = lines
+                .iter()
+                .filter(|line| line.contains("skip decision"))
+                .collect();
+            match events.as_slice() {
+                [event] if event.contains("randomStatetest0") => Ok(()),
+                _ => Err(format!("expected one skip decision event, got {events:?}")),
+            }
+        });
+    }
+
+    #[test]
+    fn test_filter_keep() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex:
+              stRandom:
+                - random.*
+            testname: {}
+            keep:
+              stRandom:
+                - randomStatetest0
+                - randomStatetest1_d0g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+        let path =
+            |name: &str| PathWrapper::from(Path::new("GeneralStateTests/stRandom").join(name));
+
+        // Then
+        assert!(!filter.is_skipped(&path("randomStatetest0.json"), None));
+        assert!(!filter.is_skipped(
+            &path("randomStatetest0.json"),
+            Some("randomStatetest0_d0g0v0_Shanghai")
+        ));
+        assert!(filter.is_skipped(&path("randomStatetest1.json"), None));
+        assert!(!filter.is_skipped(
+            &path("randomStatetest1.json"),
+            Some("randomStatetest1_d0g0v0_Shanghai")
+        ));
+        assert!(filter.is_skipped(&path("randomStatetest2.json"), None));
+    }
+
+    #[test]
+    fn test_skip_detail_wildcard_folder() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest0
+            regex:
+              "*":
+                - random.*
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let path =
+            |name: &str| PathWrapper::from(Path::new("GeneralStateTests/stRandom").join(name));
+
+        // Then
+        assert_eq!(
+            filter.skip_detail(&path("randomStatetest0.json"), None),
+            Some(SkipDetail {
+                reason: SkipReason::Filename("randomStatetest0".into()),
+                folder: Some("stRandom".to_string()),
+            })
+        );
+        assert_eq!(
+            filter.skip_detail(&path("randomStatetest1.json"), None),
+            Some(SkipDetail {
+                reason: SkipReason::Regex("random.*".into()),
+                folder: Some("*".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_filter_skip_forks() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex: {}
+            testname: {}
+            skip_forks:
+              - cancun
+            "#,
+        )
+        .unwrap();
+        let cancun = PathWrapper::from(
+            Path::new("Pyspecs/Cancun/eip4844_blobs/blob_txs.json").to_path_buf(),
+        );
+        let shanghai =
+            PathWrapper::from(Path::new("Pyspecs/shanghai/eip3855_push0/push0.json").to_path_buf());
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(&cancun, None),
+            Some(SkipReason::ForkPath("cancun".into()))
+        );
+        assert!(filter.is_skipped(&cancun, Some("blob_txs_Cancun")));
+        assert!(!filter.is_skipped(&shanghai, None));
+    }
+
+    #[test]
+    fn test_default_engine_rejects_lookahead() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex:
+              stRandom:
+                - random(?!Statetest0).*
+            testname: {}
+            ",
+        )
+        .unwrap();
+
+        // When
+        let err = filter.validate().unwrap_err();
+
+        // Then
+        assert!(matches!(err, FilterError::InvalidRegex { .. }));
+    }
+
+    #[cfg(feature = "fancy-regex")]
+    #[test]
+    fn test_fancy_engine_lookahead() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex:
+              stRandom:
+                - random(?!Statetest0).*
+            testname: {}
+            regex_engine: fancy
+            ",
+        )
+        .unwrap();
+        let path =
+            |name: &str| PathWrapper::from(Path::new("GeneralStateTests/stRandom").join(name));
+
+        // Then
+        assert!(filter.validate().is_ok());
+        assert!(!filter.is_skipped(&path("randomStatetest0.json"), None));
+        assert!(filter.is_skipped(&path("randomStatetest1.json"), None));
+    }
+
+    #[cfg(not(feature = "fancy-regex"))]
+    #[test]
+    fn test_fancy_engine_unavailable() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex:
+              stRandom:
+                - random.*
+            testname: {}
+            regex_engine: fancy
+            ",
+        )
+        .unwrap();
+
+        // Then
+        assert!(matches!(
+            filter.validate(),
+            Err(FilterError::FancyRegexUnavailable)
+        ));
+    }
+
+    #[test]
+    fn test_validate_issues() {
+        // Given
+        let yaml = r##"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - pattern: randomStatetest1
+                  issue: "#123"
+            regex:
+              stBadOpcode:
+                - pattern: opc.*
+                  issue: https://github.com/kkrt-labs/ef-tests/issues/42
+            testname: {}
+            "##;
+        let filter: Filter = serde_yaml::from_str(yaml).unwrap();
+        let strict: Filter = serde_yaml::from_str(&format!("{yaml}require_issues: true")).unwrap();
+
+        // Then
+        assert!(filter.validate_issues().is_ok());
+        assert!(matches!(
+            strict.validate_issues(),
+            Err(FilterError::MissingIssue { pattern, .. }) if pattern == "randomStatetest0"
+        ));
+    }
+
+    #[test]
+    fn test_validate_issues_invalid() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - pattern: randomStatetest0
+                  issue: see slack
+            regex: {}
+            testname: {}
+            ",
+        )
+        .unwrap();
+
+        // When
+        let err = filter.validate_issues().unwrap_err();
+
+        // Then
+        assert!(matches!(
+            err,
+            FilterError::InvalidIssue { ref issue, .. } if issue == "see slack"
+        ));
+        assert!(err.to_string().contains("stRandom"));
+    }
+
+    #[test]
+    fn test_filter_is_skipped_str() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+            regex: {}
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d1g0v0_Shanghai
+            ",
+        )
+        .unwrap();
+        let inputs = [
+            ("GeneralStateTests/stRandom/randomStatetest0.json", None),
+            ("GeneralStateTests/stRandom/randomStatetest1.json", None),
+            (
+                "GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json",
+                Some("Opcodes_TransactionInit_d1g0v0_Shanghai"),
+            ),
+            (
+                "GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json",
+                Some("Opcodes_TransactionInit_d0g0v0_Shanghai"),
+            ),
+        ];
+
+        // Then
+        assert!(filter.is_skipped_str(inputs[0].0, inputs[0].1));
+        assert!(filter.is_skipped_str(inputs[2].0, inputs[2].1));
+        for (path, case_name) in inputs {
+            assert_eq!(
+                filter.is_skipped_str(path, case_name),
+                filter.is_skipped(&PathWrapper::from(Path::new(path).to_path_buf()), case_name),
+                "{path} {case_name:?}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Error with regex pattern in filter file blockchain-tests-skip.yml: invalid regex pattern `[` in folder stFoo"
+    )]
+    fn test_filter_regex_panic_names_source() {
+        // Given
+        let mut filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex:
+              stFoo:
+                - '['
+            testname: {}
+            ",
+        )
+        .unwrap();
+        filter.source = Some(PathBuf::from("blockchain-tests-skip.yml"));
+
+        // When
+        filter.is_skipped(
+            &PathWrapper::from(Path::new("GeneralStateTests/stFoo/fooTest.json").to_path_buf()),
+            None,
+        );
+    }
+
+    #[test]
+    fn test_filter_load_file_source() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        write!(file, "filename: {{}}\nregex: {{}}\ntestname: {{}}").unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        let filter = Filter::load_file(path).unwrap();
+
+        // Then
+        assert_eq!(filter.source(), Some(file.path()));
+    }
+
+    #[test]
+    fn test_filter_test_name_wildcard() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex: {}
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_*_Shanghai
+                - Opcodes_TransactionInit_d9g0v0_Cancun
+            ",
+        )
+        .unwrap();
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json")
+                .to_path_buf(),
+        );
+
+        // Then
+        for case_name in [
+            "Opcodes_TransactionInit_d0g0v0_Shanghai",
+            "Opcodes_TransactionInit_d1g0v0_Shanghai",
+            "Opcodes_TransactionInit_d12g1v2_Shanghai",
+            "Opcodes_TransactionInit_d9g0v0_Cancun",
+        ] {
+            assert!(filter.is_skipped(&path, Some(case_name)), "{case_name}");
+        }
+        for case_name in [
+            "Opcodes_TransactionInit_d0g0v0_Cancun",
+            "Opcodes_TransactionInit_d9g0v0_Cancu",
+        ] {
+            assert!(!filter.is_skipped(&path, Some(case_name)), "{case_name}");
+        }
+    }
+
+    #[test]
+    fn test_filter_extends() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("overlays")).unwrap();
+        fs::write(
+            dir.path().join("base-skip.yml"),
+            "filename:\n  stRandom: [randomStatetest0]\nregex: {}\ntestname: {}\ncase_insensitive: true",
+        )
+        .unwrap();
+        let child_path = dir.path().join("overlays/skip.yml");
+        fs::write(
+            &child_path,
+            "extends: [../base-skip.yml]\nfilename:\n  stRandom: [randomStatetest1]\nregex: {}\ntestname: {}",
+        )
+        .unwrap();
+        let path =
+            |name: &str| PathWrapper::from(Path::new("GeneralStateTests/stRandom").join(name));
+
+        // When
+        let filter = Filter::load_file(child_path.to_str().unwrap()).unwrap();
+
+        // Then
+        assert!(filter.is_skipped(&path("randomStatetest0.json"), None));
+        assert!(filter.is_skipped(&path("RANDOMSTATETEST1.json"), None));
+        assert!(!filter.is_skipped(&path("randomStatetest2.json"), None));
+        assert!(filter.extends.is_empty());
+    }
+
+    #[test]
+    fn test_filter_extends_cycle() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let content =
+            |base: &str| format!("extends: [{base}]\nfilename: {{}}\nregex: {{}}\ntestname: {{}}");
+        fs::write(dir.path().join("a.yml"), content("b.yml")).unwrap();
+        fs::write(dir.path().join("b.yml"), content("a.yml")).unwrap();
+
+        // When
+        let err = Filter::load_file(dir.path().join("a.yml").to_str().unwrap()).unwrap_err();
+
+        // Then
+        assert!(err.to_string().contains("a.yml extends itself"), "{err}");
+    }
+
+    #[test]
+    fn test_filter_skip_mode() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - pattern: randomStatetest1
+                  mode: ignore
+                - pattern: randomStatetest2
+                  mode: omit
+                - pattern: randomStatetest3
+                  mode: ignore
+                  severity: warn
+            regex: {}
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let skip_mode = |name: &str| {
+            filter.skip_mode(
+                &PathWrapper::from(Path::new("GeneralStateTests/stRandom").join(name)),
+                None,
+            )
+        };
+
+        // Then
+        assert_eq!(skip_mode("randomStatetest0.json"), Some(SkipMode::Omit));
+        assert_eq!(skip_mode("randomStatetest1.json"), Some(SkipMode::Ignore));
+        assert_eq!(skip_mode("randomStatetest2.json"), Some(SkipMode::Omit));
+        assert_eq!(skip_mode("randomStatetest3.json"), None);
+        assert_eq!(skip_mode("randomStatetest4.json"), None);
+    }
+
+    #[test]
+    fn test_filter_may_apply_agrees_with_skip_reason() {
+        // Given
+        let filters: Vec<Filter> = [
+            "filename:\n  stRandom: [randomStatetest0]\nregex: {}\ntestname: {}",
+            "filename: {}\nregex:\n  '*': [random.*]\ntestname: {}",
+            "filename: {}\nregex: {}\ntestname: {}\nfork:\n  stRandom:\n    Shanghai: [randomStatetest0]",
+            "filename:\n  STRANDOM: [RANDOMSTATETEST0]\nregex: {}\ntestname: {}\ncase_insensitive: true",
+            "filename:\n  GeneralStateTests: [randomStatetest0]\nregex: {}\ntestname: {}\ncascade: true",
+        ]
+        .into_iter()
+        .map(|filter| serde_yaml::from_str(filter).unwrap())
+        .collect();
+        let paths = [
+            "GeneralStateTests/stRandom/randomStatetest0.json",
+            "GeneralStateTests/stRandom/randomStatetest1.json",
+            "GeneralStateTests/stBadOpcode/randomStatetest0.json",
+        ];
+
+        // Then
+        for (index, filter) in filters.iter().enumerate() {
+            for path in paths {
+                let path = PathWrapper::from(Path::new(path).to_path_buf());
+                for case_name in [None, Some("randomStatetest0_d0g0v0_Shanghai".to_string())] {
+                    let expected = filter
+                        .skip_reason(&path, case_name.as_deref())
+                        .map_or(false, |reason| reason.severity().is_skip());
+                    assert_eq!(
+                        filter.is_skipped(&path, case_name.as_deref()),
+                        expected,
+                        "filter {index}, {path:?}, {case_name:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Returns test paths spread over 100 folders, most of them without entries
+    pub(super) fn bench_paths() -> Vec<PathWrapper> {
+        (0..100_000)
+            .map(|index| {
+                PathWrapper::from(
+                    Path::new("GeneralStateTests")
+                        .join(format!("stFolder{}", index % 100))
+                        .join(format!("test{index}.json")),
+                )
+            })
+            .collect()
+    }
+
+    /// Times `is_skipped` over the paths and prints the duration per call.
+    pub(super) fn bench(
+        name: &str,
+        paths: &[PathWrapper],
+        is_skipped: impl Fn(&PathWrapper) -> bool,
+    ) {
+        let start = std::time::Instant::now();
+        let skipped = paths.iter().filter(|path| is_skipped(path)).count();
+        let elapsed = start.elapsed();
+
+        println!(
+            "{name}: {} calls in {elapsed:?} ({:?} per call), {skipped} skipped",
+            paths.len(),
+            elapsed / paths.len() as u32
+        );
+    }
+
+    /// Compares `is_skipped`, which starts with the `may_apply` fast path, with the
+    /// full lookup of `skip_reason`. Run with `cargo test -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_is_skipped() {
+        let filter = Filter::embedded();
+        let paths = bench_paths();
+
+        bench("is_skipped", &paths, |path| filter.is_skipped(path, None));
+        bench("skip_reason", &paths, |path| {
+            filter
+                .skip_reason(path, None)
+                .map_or(false, |reason| reason.severity().is_skip())
+        });
+    }
+
+    #[test]
+    fn test_filter_all_files() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - __all__
+            regex: {}
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let path = |path: &str| PathWrapper::from(Path::new(path).to_path_buf());
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(&path("GeneralStateTests/stRandom/anyTest.json"), None),
+            Some(SkipReason::Filename("__all__".into()))
+        );
+        assert!(!filter.is_skipped(&path("GeneralStateTests/stBadOpcode/anyTest.json"), None));
+    }
+
+    #[test]
+    fn test_filter_match_target() {
+        // Given
+        let path = PathWrapper::from(
+            Path::new("BlockchainTests/GeneralStateTests/stRandom/randomStatetest0.json")
+                .to_path_buf(),
+        );
+        let filter = |target: &str, pattern: &str| -> Filter {
+            serde_yaml::from_str(&format!(
+                r"
+                filename:
+                  stRandom:
+                    - {pattern}
+                regex: {{}}
+                testname: {{}}
+                match_target: {target}
+                "
+            ))
+            .unwrap()
+        };
+
+        // Then
+        assert!(filter("stem", "randomStatetest0").is_skipped(&path, None));
+        assert!(!filter("stem", "randomStatetest0.json").is_skipped(&path, None));
+        assert!(filter("file_name", "randomStatetest0.json").is_skipped(&path, None));
+        assert!(!filter("file_name", "randomStatetest0").is_skipped(&path, None));
+        assert!(filter(
+            "relative_path",
+            "GeneralStateTests/stRandom/randomStatetest0.json"
+        )
+        .is_skipped(&path, None));
+        assert!(!filter("relative_path", "randomStatetest0.json").is_skipped(&path, None));
+    }
+
+    #[test]
+    fn test_filter_extension() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex: {}
+            testname: {}
+            extension:
+              stRandom:
+                - .yml
+            ",
+        )
+        .unwrap();
+        let path =
+            |name: &str| PathWrapper::from(Path::new("GeneralStateTests/stRandom").join(name));
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(&path("randomStatetest0.yml"), None),
+            Some(SkipReason::Extension(".yml".into()))
+        );
+        assert!(!filter.is_skipped(&path("randomStatetest0.json"), None));
+        assert!(!filter.is_skipped(
+            &PathWrapper::from(
+                Path::new("GeneralStateTests/stBadOpcode/opc4DDiffPlaces.yml").to_path_buf()
+            ),
+            None
+        ));
+        let (skipped, _) = filter.partition_cases(
+            &path("randomStatetest0.yml"),
+            &["randomStatetest0_d0g0v0_Shanghai".to_string()],
+        );
+        assert_eq!(skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_filter_hash() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("stRandom")).unwrap();
+        let file = dir.path().join("stRandom/randomStatetest0.json");
+        let content = r#"{"randomStatetest0_d0g0v0_Shanghai": {}}"#;
+        fs::write(&file, content).unwrap();
+        let digest = format!("{:x}", Sha256::digest(content));
+        let filter = Filter::from_yaml_str(&format!(
+            "filename: {{}}\nregex: {{}}\ntestname: {{}}\nhash:\n  stRandom: [{digest}]"
+        ))
+        .unwrap();
+        let path = PathWrapper::from(file.clone());
+
+        // Then
+        assert_eq!(
+            filter.skip_reason(&path, None),
+            Some(SkipReason::Hash(digest.as_str().into()))
+        );
+        assert_eq!(
+            filter
+                .partition_cases(&path, &["randomStatetest0_d0g0v0_Shanghai".to_string()])
+                .0
+                .len(),
+            1
+        );
+
+        // When
+        fs::write(&file, r#"{"randomStatetest0_d0g0v0_Cancun": {}}"#).unwrap();
+
+        // Then
+        assert!(!filter.is_skipped(&path, None));
+        assert!(matches!(
+            Filter::from_yaml_str("filename: {}\nregex: {}\ntestname: {}\nhash:\n  stRandom: [abc]"),
+            Err(FilterError::InvalidHash { pattern, .. }) if pattern == "abc"
+        ));
+    }
+
+    #[test]
+    fn test_filter_negated_regex() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex:
+              stRandom:
+                - "!keepme"
+              stBadOpcode:
+                - "!opc.*"
+                - "!badOpcodes"
+                - opcA5DiffPlaces
+                - invalid.*
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let path = |path: &str| PathWrapper::from(Path::new("GeneralStateTests").join(path));
+
+        // Then
+        assert!(!filter.is_skipped(&path("stRandom/keepme.json"), None));
+        assert!(filter.is_skipped(&path("stRandom/randomStatetest0.json"), None));
+        assert!(filter.is_skipped(&path("stRandom/keepme2.json"), None));
+        assert!(!filter.is_skipped(&path("stCreate2/randomStatetest0.json"), None));
+
+        assert!(!filter.is_skipped(&path("stBadOpcode/opc4DDiffPlaces.json"), None));
+        assert!(!filter.is_skipped(&path("stBadOpcode/badOpcodes.json"), None));
+        // The negated patterns take precedence over the plain ones
+        assert!(!filter.is_skipped(&path("stBadOpcode/opcA5DiffPlaces.json"), None));
+        assert!(filter.is_skipped(&path("stBadOpcode/invalidDiffPlaces.json"), None));
+        assert!(filter.is_skipped(&path("stBadOpcode/measureGas.json"), None));
+    }
+
+    #[test]
+    fn test_classify() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+            regex:
+              stRandom:
+                - pattern: randomStatetest.*
+                  severity: warn
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let path =
+            |name: &str| PathWrapper::from(Path::new("GeneralStateTests/stRandom").join(name));
+
+        // Then
+        assert_eq!(
+            filter.classify(&path("randomStatetest0.json"), None),
+            Classification::Skip(SkipReason::Filename("randomStatetest0".into()))
+        );
+        let warn_only = filter.classify(&path("randomStatetest1.json"), None);
+        assert!(
+            matches!(&warn_only, Classification::WarnOnly(SkipReason::Regex(entry)) if entry.pattern() == "randomStatetest.*")
+        );
+        assert_eq!(
+            filter.classify(&path("otherTest.json"), None),
+            Classification::Run
+        );
+        assert!(filter.is_skipped(&path("randomStatetest0.json"), None));
+        assert!(!filter.is_skipped(&path("randomStatetest1.json"), None));
+    }
+
+    #[test]
+    fn test_classify_folder_default_severity() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - pattern: randomStatetest1
+                  severity: skip
+              stBadOpcode:
+                - opc4DDiffPlaces
+            testname: {}
+            regex: {}
+            folders:
+              stRandom:
+                default_severity: warn
+            ",
+        )
+        .unwrap();
+        let path = |path: &str| PathWrapper::from(Path::new(path).to_path_buf());
+
+        // When
+        let warn_only = filter.classify(
+            &path("GeneralStateTests/stRandom/randomStatetest0.json"),
+            None,
+        );
+        let overridden = filter.classify(
+            &path("GeneralStateTests/stRandom/randomStatetest1.json"),
+            None,
+        );
+        let skipped = filter.classify(
+            &path("GeneralStateTests/stBadOpcode/opc4DDiffPlaces.json"),
+            None,
+        );
+
+        // Then
+        assert!(
+            matches!(&warn_only, Classification::WarnOnly(SkipReason::Filename(entry)) if entry.pattern() == "randomStatetest0")
+        );
+        assert!(matches!(overridden, Classification::Skip(_)));
+        assert_eq!(
+            skipped,
+            Classification::Skip(SkipReason::Filename("opc4DDiffPlaces".into()))
+        );
+        assert!(!filter.is_skipped(
+            &path("GeneralStateTests/stRandom/randomStatetest0.json"),
+            None
+        ));
+        assert!(!filter
+            .scoped("stRandom")
+            .is_file_skipped(&path("GeneralStateTests/stRandom/randomStatetest0.json")));
+    }
+
+    #[test]
+    fn test_has_runnable_case() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+            regex: {}
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d0g0v0_Shanghai
+            ",
+        )
+        .unwrap();
+        let path = |path: &str| PathWrapper::from(Path::new(path).to_path_buf());
+        let cases = |names: &[&str]| names.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        // Then
+        assert!(!filter.has_runnable_case(
+            &path("GeneralStateTests/stRandom/randomStatetest0.json"),
+            &cases(&[
+                "randomStatetest0_d0g0v0_Shanghai",
+                "randomStatetest0_d1g0v0_Shanghai"
+            ])
+        ));
+        let transaction = path("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json");
+        assert!(filter.has_runnable_case(
+            &transaction,
+            &cases(&[
+                "Opcodes_TransactionInit_d0g0v0_Shanghai",
+                "Opcodes_TransactionInit_d1g0v0_Shanghai"
+            ])
+        ));
+        assert!(!filter.has_runnable_case(
+            &transaction,
+            &cases(&["Opcodes_TransactionInit_d0g0v0_Shanghai"])
+        ));
+    }
+
+    #[test]
+    fn test_filter_index() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex: {}
+            testname: {}
+            index:
+              stTransactionTest:
+                - d1-2v0
+            ",
+        )
+        .unwrap();
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json")
+                .to_path_buf(),
+        );
+        let is_skipped = |case_name: &str| filter.is_skipped(&path, Some(case_name));
+
+        // Then
+        assert!(is_skipped("Opcodes_TransactionInit_d1g0v0_Shanghai"));
+        assert!(is_skipped("Opcodes_TransactionInit_d2g3v0_Shanghai"));
+        assert!(!is_skipped("Opcodes_TransactionInit_d0g0v0_Shanghai"));
+        assert!(!is_skipped("Opcodes_TransactionInit_d1g0v1_Shanghai"));
+        assert!(!filter.is_skipped(&path, None));
+        assert_eq!(
+            filter.skip_reason(&path, Some("Opcodes_TransactionInit_d1g0v0_Shanghai")),
+            Some(SkipReason::Index("d1-2v0".into()))
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_index() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex: {}
+            testname: {}
+            index:
+              stTransactionTest:
+                - x0
+            ",
+        )
+        .unwrap();
+
+        // When
+        let err = filter.validate().unwrap_err();
+
+        // Then
+        assert!(matches!(err, FilterError::InvalidIndex { .. }));
+    }
+
+    #[test]
+    fn test_filter_cascade() {
+        // Given
+        let yaml = r"
+            filename: {}
+            regex:
+              Pyspecs:
+                - .*
+            testname: {}
+            ";
+        let filter: Filter = serde_yaml::from_str(yaml).unwrap();
+        let cascade: Filter = serde_yaml::from_str(&format!("{yaml}cascade: true")).unwrap();
+        let nested = PathWrapper::from(
+            Path::new("BlockchainTests/Pyspecs/cancun/eip4844_blobs/blob_txs.json").to_path_buf(),
+        );
+        let other = PathWrapper::from(
+            Path::new("BlockchainTests/GeneralStateTests/stRandom/randomStatetest0.json")
+                .to_path_buf(),
+        );
+
+        // Then
+        assert!(!filter.is_skipped(&nested, None));
+        assert!(cascade.is_skipped(&nested, None));
+        assert_eq!(
+            cascade.skip_detail(&nested, None).unwrap().folder,
+            Some("Pyspecs".to_string())
+        );
+        assert!(!cascade.is_skipped(&other, None));
+    }
+
+    #[test]
+    fn test_filter_cascade_grandparent() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stZeroKnowledge2:
+                - ecmul_0-0_0_21000_0
+            regex: {}
+            testname: {}
+            cascade: true
+            ",
+        )
+        .unwrap();
+        let path = |parent: &str| {
+            PathWrapper::from(
+                Path::new("GeneralStateTests")
+                    .join(parent)
+                    .join("ecmul_0-0_0_21000_0.json"),
+            )
+        };
+
+        // Then
+        assert!(filter.is_skipped(&path("stZeroKnowledge2/ecmul"), None));
+        assert!(filter.is_skipped(&path("stZeroKnowledge2"), None));
+        assert!(!filter.is_skipped(&path("stZeroKnowledge/ecmul"), None));
+    }
+
+    #[test]
+    fn test_filter_path_scoped() {
+        // Given
+        let yaml = r#"
+            filename:
+              GeneralStateTests/stCreate2:
+                - create2collisionBalance
+            regex: {}
+            testname: {}
+            "#;
+        let leaf_scoped: Filter = serde_yaml::from_str(yaml).unwrap();
+        let path_scoped: Filter =
+            serde_yaml::from_str(&format!("{yaml}path_scoped: true")).unwrap();
+        let state_test = PathWrapper::from(
+            Path::new(
+                "ethereum-tests/BlockchainTests/GeneralStateTests/stCreate2/create2collisionBalance.json",
+            )
+            .to_path_buf(),
+        );
+        let other_test = PathWrapper::from(
+            Path::new(
+                "ethereum-tests/BlockchainTests/Pyspecs/stCreate2/create2collisionBalance.json",
+            )
+            .to_path_buf(),
+        );
+
+        // Then
+        assert!(!leaf_scoped.is_skipped(&state_test, None));
+        assert!(path_scoped.is_skipped(&state_test, None));
+        assert!(!path_scoped.is_skipped(&other_test, None));
+    }
+
+    #[test]
+    fn test_filter_case_insensitive() {
+        // Given
+        let yaml = r#"
+            filename:
+              stcallcreatecallcodetest:
+                - call1024precalls
+            regex:
+              stBadOpcode:
+                - ^OPC.*
+            testname:
+              stTransactionTest:
+                - opcodes_transactioninit_d111g0v0_shanghai
+            "#;
+        let case_sensitive: Filter = serde_yaml::from_str(yaml).unwrap();
+        let case_insensitive: Filter =
+            serde_yaml::from_str(&format!("{yaml}case_insensitive: true")).unwrap();
+        let filename = PathWrapper::from(
+            Path::new("GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json")
+                .to_path_buf(),
+        );
+        let regex = PathWrapper::from(
+            Path::new("GeneralStateTests/stBadOpcode/opc4DDiffPlaces.json").to_path_buf(),
+        );
+        let test_name = PathWrapper::from(
+            Path::new("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json")
+                .to_path_buf(),
+        );
+        let case_name = Some("Opcodes_TransactionInit_d111g0v0_Shanghai");
+
+        // Then
+        assert!(!case_sensitive.is_skipped(&filename, None));
+        assert!(!case_sensitive.is_skipped(&regex, None));
+        assert!(!case_sensitive.is_skipped(&test_name, case_name));
+        assert!(case_insensitive.is_skipped(&filename, None));
+        assert!(case_insensitive.is_skipped(&regex, None));
+        assert!(case_insensitive.is_skipped(&test_name, case_name));
+    }
+
+    #[test]
+    fn test_entries() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stBadOpcode:
+                - pattern: opc.*
+                  reason: "flaky gas accounting #123"
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d0g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let entries: Vec<_> = filter.entries().collect();
+
+        // Then
+        let expected = vec![
+            FilterEntryRef {
+                folder: "stCallCreateCallCodeTest",
+                category: Category::Filename,
+                pattern: "Call1024PreCalls",
+                reason: None,
+                expires: None,
+            },
+            FilterEntryRef {
+                folder: "stBadOpcode",
+                category: Category::Regex,
+                pattern: "opc.*",
+                reason: Some("flaky gas accounting #123"),
+                expires: None,
+            },
+            FilterEntryRef {
+                folder: "stTransactionTest",
+                category: Category::TestName,
+                pattern: "Opcodes_TransactionInit_d0g0v0_Shanghai",
+                reason: None,
+                expires: None,
+            },
+        ];
+        assert_eq!(entries, expected);
+    }
+
+    #[test]
+    fn test_expired() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - pattern: randomStatetest1
+                  expires: 2024-01-31
+                - pattern: randomStatetest2
+                  reason: "flaky gas accounting #123"
+                  expires: 2024-03-31
+            regex: {}
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun:
+                  - pattern: Opcodes_TransactionInit
+                    expires: 2020-01-01
+            extension:
+              stRandom:
+                - pattern: yml
+                  expires: 2020-01-01
+            "#,
+        )
+        .unwrap();
+        let today = NaiveDate::from_ymd_opt(2024, 2, 15).unwrap();
+
+        // When
+        let expired = filter.expired(today);
+
+        // Then
+        assert_eq!(
+            expired,
+            vec![
+                ExpiredEntry {
+                    folder: "stRandom",
+                    map: "filename",
+                    fork: None,
+                    pattern: "randomStatetest1",
+                    reason: None,
+                    expires: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                },
+                ExpiredEntry {
+                    folder: "stRandom",
+                    map: "extension",
+                    fork: None,
+                    pattern: "yml",
+                    reason: None,
+                    expires: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+                },
+                ExpiredEntry {
+                    folder: "stTransactionTest",
+                    map: "fork",
+                    fork: Some("Cancun"),
+                    pattern: "Opcodes_TransactionInit",
+                    reason: None,
+                    expires: NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_len() {
+        // Given
+        let empty: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom: []
+            regex: {}
+            testname: {}
+            only:
+              stRandom:
+                - randomStatetest0
+            "#,
+        )
+        .unwrap();
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - randomStatetest1
+            regex:
+              stBadOpcode:
+                - opc.*
+            testname: {}
+            fork:
+              stTransactionTest:
+                Cancun:
+                  - Opcodes_TransactionInit
+            "#,
+        )
+        .unwrap();
+
+        // Then
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert!(!filter.is_empty());
+        assert_eq!(filter.len(), 4);
+    }
+
+    #[test]
+    fn test_is_included_empty_only() {
+        // Given
+        let filter = Filter::default();
+        let path = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest0.json").to_path_buf(),
+        );
+
+        // When
+        let is_included = filter.is_included(&path, None);
+
+        // Then
+        assert!(is_included);
+    }
+
+    #[test]
+    fn test_is_included() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename: {}
+            regex: {}
+            testname: {}
+            only:
+              stRandom:
+                - randomStatetest0
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls_d0g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+        let included = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest0.json").to_path_buf(),
+        );
+        let excluded = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest1.json").to_path_buf(),
+        );
+        let other_folder = PathWrapper::from(
+            Path::new("GeneralStateTests/stBadOpcode/randomStatetest0.json").to_path_buf(),
+        );
+        let case = PathWrapper::from(
+            Path::new("GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json")
+                .to_path_buf(),
+        );
+
+        // Then
+        assert!(filter.is_included(&included, None));
+        assert!(!filter.is_included(&excluded, None));
+        assert!(!filter.is_included(&other_folder, None));
+        assert!(filter.is_included(&case, Some("Call1024PreCalls_d0g0v0_Shanghai")));
+        assert!(!filter.is_included(&case, Some("Call1024PreCalls_d1g0v0_Shanghai")));
+    }
+
+    #[test]
+    fn test_merge() {
+        // Given
+        let mut lhs: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stBadOpcode:
+                - ^opc.*
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let rhs: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+                - Callcode1024BalanceTooLow
+              stEIP3855-push0:
+                - push0
+            regex: {}
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d111g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+
+        // When
+        lhs.merge(rhs);
+
+        // Then
+        assert_eq!(
+            lhs.filename["stCallCreateCallCodeTest"],
+            vec![
+                SkipEntry::from("Call1024PreCalls"),
+                SkipEntry::from("Callcode1024BalanceTooLow")
+            ]
+        );
+        assert_eq!(
+            lhs.filename["stEIP3855-push0"],
+            vec![SkipEntry::from("push0")]
+        );
+        assert_eq!(lhs.regex["stBadOpcode"], vec![SkipEntry::from("^opc.*")]);
+        assert_eq!(
+            lhs.test_name["stTransactionTest"],
+            vec![SkipEntry::from("Opcodes_TransactionInit_d111g0v0_Shanghai")]
+        );
+    }
+
+    #[test]
+    fn test_for_profile() {
+        // Given
+        let filter = Filter::from_yaml_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest0
+            profiles:
+              default:
+                filename:
+                  stCallCreateCallCodeTest:
+                    - Call1024PreCalls
+                regex:
+                  "*":
+                    - Callcode.*
+              nightly:
+                filename:
+                  stCallCreateCallCodeTest:
+                    - Call1024PreCalls
+            "#,
+        )
+        .unwrap();
+        let path = |path: &str| PathWrapper::from(Path::new(path).to_path_buf());
+
+        // When
+        let default = filter.for_profile("default").unwrap();
+        let nightly = filter.for_profile("nightly").unwrap();
+
+        // Then
+        assert_eq!(
+            filter.profiles().collect::<Vec<_>>(),
+            ["default", "nightly"]
+        );
+        assert_eq!(
+            nightly.relation(&default),
+            FilterRelation::StrictlyMorePermissive
+        );
+        let callcode =
+            path("GeneralStateTests/stCallCreateCallCodeTest/Callcode1024BalanceTooLow.json");
+        assert!(default.is_skipped(&callcode, None));
+        assert!(!nightly.is_skipped(&callcode, None));
+        for filter in [&default, &nightly] {
+            assert!(filter.is_skipped(
+                &path("GeneralStateTests/stRandom/randomStatetest0.json"),
+                None
+            ));
+            assert_eq!(filter.profiles().count(), 0);
+        }
+        assert_eq!(filter.for_profile("unknown"), None);
+    }
+
+    #[cfg(feature = "fancy-regex")]
+    #[test]
+    fn test_validate_profile_fancy_engine() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            regex_engine: fancy
+            profiles:
+              nightly:
+                regex:
+                  stRandom:
+                    - random(?!Statetest0).*
+            ",
+        )
+        .unwrap();
+
+        // Then
+        assert!(filter.validate().is_ok());
+    }
+
+    #[test]
+    fn test_merge_preferring() {
+        // Given
+        let lhs: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+              stRandom:
+                - randomStatetest0
+            regex: {}
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let rhs: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stCallCreateCallCodeTest:
+                - Callcode1024BalanceTooLow
+              stEIP3855-push0:
+                - push0
+            regex: {}
+            testname: {}
+            ",
+        )
+        .unwrap();
+        let merged = |policy: MergePolicy| {
+            let mut filter = lhs.clone();
+            filter.merge_preferring(rhs.clone(), policy);
+            filter
+        };
+
+        // When
+        let union = merged(MergePolicy::Union);
+        let prefer_self = merged(MergePolicy::PreferSelf);
+        let prefer_other = merged(MergePolicy::PreferOther);
+
+        // Then
+        assert_eq!(
+            union.filename["stCallCreateCallCodeTest"],
+            vec![
+                SkipEntry::from("Call1024PreCalls"),
+                SkipEntry::from("Callcode1024BalanceTooLow")
+            ]
+        );
+        assert_eq!(
+            prefer_self.filename["stCallCreateCallCodeTest"],
+            vec![SkipEntry::from("Call1024PreCalls")]
+        );
+        assert_eq!(
+            prefer_other.filename["stCallCreateCallCodeTest"],
+            vec![SkipEntry::from("Callcode1024BalanceTooLow")]
+        );
+        for filter in [&union, &prefer_self, &prefer_other] {
+            assert_eq!(
+                filter.filename["stRandom"],
+                vec![SkipEntry::from("randomStatetest0")]
+            );
+            assert_eq!(
+                filter.filename["stEIP3855-push0"],
+                vec![SkipEntry::from("push0")]
+            );
+        }
+        let mut merged = lhs.clone();
+        merged.merge(rhs.clone());
+        assert_eq!(merged, union);
+    }
+
+    #[test]
+    fn test_load_files() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        writeln!(
+            file,
+            "filename:\n  stRandom:\n    - randomStatetest0\nregex: {{}}\ntestname: {{}}"
+        )
+        .unwrap();
+        let paths = [
+            "../../blockchain-tests-skip.yml",
+            file.path().to_str().unwrap(),
+        ];
+
+        // When
+        let filter = Filter::load_files(&paths).unwrap();
+
+        // Then
+        let regex = PathWrapper::from(
+            Path::new("GeneralStateTests/stBadOpcode/opc4DDiffPlaces.json").to_path_buf(),
+        );
+        let random = PathWrapper::from(
+            Path::new("GeneralStateTests/stRandom/randomStatetest0.json").to_path_buf(),
+        );
+        assert!(filter.is_skipped(&regex, None));
+        assert!(filter.is_skipped(&random, None));
+    }
+
+    #[test]
+    fn test_load_dir() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("b-opcodes.yaml"),
+            "filename:\n  stRandom:\n    - randomStatetest1\nregex: {}\ntestname: {}",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("a-precompiles.yml"),
+            "filename:\n  stRandom:\n    - randomStatetest0\nregex: {}\ntestname: {}",
+        )
+        .unwrap();
+        fs::write(dir.path().join("README.md"), "not a filter").unwrap();
+
+        // When
+        let filter = Filter::load_dir(dir.path().to_str().unwrap()).unwrap();
+
+        // Then
+        assert_eq!(
+            filter.filename["stRandom"],
+            vec![
+                SkipEntry::from("randomStatetest0"),
+                SkipEntry::from("randomStatetest1")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_dir_invalid_file() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("valid.yml"),
+            "filename: {}\nregex: {}\ntestname: {}",
+        )
+        .unwrap();
+        fs::write(dir.path().join("invalid.yml"), "filename: [").unwrap();
+
+        // When
+        let err = Filter::load_dir(dir.path().to_str().unwrap()).unwrap_err();
+
+        // Then
+        assert!(err.to_string().contains("invalid.yml"));
+    }
+
+    #[test]
+    fn test_normalize() {
+        // Given
+        let mut filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest1
+                - randomStatetest0
+                - randomStatetest1
+              stEmpty: []
+            regex:
+              stBadOpcode:
+                - pattern: opc.*
+                  reason: "flaky gas accounting #123"
+                - opc.*
+            testname:
+              stEmpty: []
+            fork:
+              stTransactionTest:
+                Cancun: []
+            skip_forks:
+              - cancun
+              - cancun
+            "#,
+        )
+        .unwrap();
+
+        // When
+        filter.normalize();
+
+        // Then
+        assert_eq!(
+            filter.filename,
+            BTreeMap::from([(
+                "stRandom".to_string(),
+                vec!["randomStatetest0".into(), "randomStatetest1".into()]
+            )])
+        );
+        assert_eq!(
+            filter.regex["stBadOpcode"],
+            vec![
+                "opc.*".into(),
+                SkipEntry::Detailed {
+                    pattern: "opc.*".to_string(),
+                    reason: Some("flaky gas accounting #123".to_string()),
+                    expires: None,
+                    issue: None,
+                    severity: Severity::Skip,
+                    mode: SkipMode::Omit,
+                }
+            ]
+        );
+        assert!(filter.test_name.is_empty());
+        assert!(filter.fork.is_empty());
+        assert_eq!(filter.skip_forks, vec!["cancun"]);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        // Given
+        let filter = |yaml: &str| -> Filter { serde_yaml::from_str(yaml).unwrap() };
+        let lhs = filter(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - randomStatetest1
+            regex: {}
+            testname: {}
+            ",
+        );
+        let reordered = filter(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest1
+                - randomStatetest0
+                - randomStatetest0
+            regex: {}
+            testname: {}
+            ",
+        );
+        let extended = filter(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - randomStatetest1
+                - randomStatetest2
+            regex: {}
+            testname: {}
+            ",
+        );
+
+        // Then
+        let fingerprint = lhs.fingerprint();
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(fingerprint, reordered.fingerprint());
+        assert_ne!(fingerprint, extended.fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_same_pattern() {
+        // Given
+        let filter = |yaml: &str| -> Filter { serde_yaml::from_str(yaml).unwrap() };
+        let lhs = filter(
+            r"
+            filename:
+              stRandom:
+                - pattern: randomStatetest0
+                  severity: warn
+                - pattern: randomStatetest0
+                  mode: ignore
+            ",
+        );
+        let mut reordered = filter(
+            r"
+            filename:
+              stRandom:
+                - pattern: randomStatetest0
+                  mode: ignore
+                - pattern: randomStatetest0
+                  severity: warn
+            ",
+        );
+
+        // When
+        let fingerprint = lhs.fingerprint();
+        reordered.normalize();
+
+        // Then
+        assert_eq!(fingerprint, reordered.fingerprint());
+        assert_eq!(reordered.filename["stRandom"].len(), 2);
+    }
+
+    #[test]
+    fn test_save_file() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest1
+                - randomStatetest0
+              stCallCreateCallCodeTest:
+                - pattern: Callcode1024BalanceTooLow
+                  reason: "flaky gas accounting #123"
+                - Call1024PreCalls
+            regex:
+              stMemoryStressTest:
+                - MLOAD_Bounds.*
+                - CALL_Bounds.*
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let file = tempfile::Builder::new().suffix(".yml").tempfile().unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        filter.save_file(path).unwrap();
+        let saved = fs::read_to_string(path).unwrap();
+        let reloaded = Filter::load_file(path).unwrap();
+
+        // Then
+        let mut expected = filter.clone();
+        expected.sort();
+        assert_eq!(reloaded.filename, expected.filename);
+        assert_eq!(reloaded.regex, expected.regex);
+        assert_eq!(reloaded.test_name, expected.test_name);
+        assert_eq!(
+            reloaded.filename["stRandom"],
+            vec![
+                SkipEntry::from("randomStatetest0"),
+                SkipEntry::from("randomStatetest1")
+            ]
+        );
+        reloaded.save_file(path).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), saved);
+    }
+}
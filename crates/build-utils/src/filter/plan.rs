@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::{walk::test_files, Filter};
+use crate::path::PathWrapper;
+
+/// Test case which will run
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct PlannedCase {
+    /// Path of the test file, relative to the tests root
+    pub file: PathBuf,
+    /// Name of the test case
+    pub case: String,
+}
+
+/// Test cases which will run after applying a filter, built by [`Filter::plan`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RunPlan {
+    /// The cases which will run, sorted by file and case name
+    pub cases: Vec<PlannedCase>,
+    /// Number of cases skipped
+    pub skipped: usize,
+}
+
+impl RunPlan {
+    /// Returns the cases of the plan assigned to the worker `index` out of `total`
+    /// workers. Cases are assigned by a stable hash of their file path and name, so
+    /// that the shards are disjoint, reproducible and cover the whole plan. The
+    /// skipped count is kept as is.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not lower than `total`.
+    pub fn shard(&self, total: usize, index: usize) -> Self {
+        assert!(
+            index < total,
+            "Shard index {index} out of range for {total} shards"
+        );
+        let cases = self
+            .cases
+            .iter()
+            .filter(|case| case.shard_hash() % total as u64 == index as u64)
+            .cloned()
+            .collect();
+        Self {
+            cases,
+            skipped: self.skipped,
+        }
+    }
+}
+
+impl PlannedCase {
+    /// Returns the FNV-1a hash of the file path and the case name, which unlike
+    /// the standard library hashers is stable across platforms and releases. The
+    /// path is hashed with `/` separators, so that Windows workers agree with the
+    /// others.
+    fn shard_hash(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0100_0000_01b3;
+
+        let file = PathWrapper::from(self.file.clone()).to_string();
+        let bytes = file.bytes().chain([0]).chain(self.case.bytes());
+        bytes.fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+    }
+}
+
+impl Filter {
+    /// Walks the test files under `test_root` and returns the cases which will
+    /// run. Cases excluded by the `only` mapping are counted as skipped. The
+    /// `progress` callback is invoked for each visited file.
+    pub fn plan(&self, test_root: &Path, progress: Option<&mut dyn FnMut(&Path)>) -> RunPlan {
+        let mut plan = RunPlan::default();
+
+        for path in test_files(test_root, progress) {
+            let file = path
+                .relative_to(test_root)
+                .map_or_else(|| path.clone().into(), PathBuf::from);
+            for case in path.read_case_names().unwrap_or_default() {
+                if self.is_excluded(&path, Some(&case)) {
+                    plan.skipped += 1;
+                } else {
+                    plan.cases.push(PlannedCase {
+                        file: file.clone(),
+                        case,
+                    });
+                }
+            }
+        }
+        plan.cases.sort();
+
+        plan
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_DATA: &str = "src/test_data/BlockchainTests/GeneralStateTests";
+
+    #[test]
+    fn test_plan() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stRandom:
+                - randomStatetest0
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d1g0v0_Shanghai
+            keep:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls_d0g1v0_Shanghai
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let plan = filter.plan(Path::new(TEST_DATA), None);
+
+        // Then
+        let case = |file: &str, case: &str| PlannedCase {
+            file: PathBuf::from(file),
+            case: case.to_string(),
+        };
+        assert_eq!(
+            plan.cases,
+            vec![
+                case(
+                    "stCallCreateCallCodeTest/Call1024PreCalls.json",
+                    "Call1024PreCalls_d0g1v0_Shanghai"
+                ),
+                case(
+                    "stCallCreateCallCodeTest/Callcode1024BalanceTooLow.json",
+                    "Callcode1024BalanceTooLow_d0g0v0_Shanghai"
+                ),
+                case(
+                    "stRandom/randomStatetest1.json",
+                    "randomStatetest1_d0g0v0_Shanghai"
+                ),
+                case(
+                    "stTransactionTest/Opcodes_TransactionInit.json",
+                    "Opcodes_TransactionInit_d0g0v0_Cancun"
+                ),
+                case(
+                    "stTransactionTest/Opcodes_TransactionInit.json",
+                    "Opcodes_TransactionInit_d0g0v0_Shanghai"
+                ),
+            ]
+        );
+        assert_eq!(plan.skipped, 3);
+    }
+
+    #[test]
+    fn test_shard() {
+        // Given
+        let plan = RunPlan {
+            cases: (0..100)
+                .map(|i| PlannedCase {
+                    file: PathBuf::from(format!("stRandom/randomStatetest{}.json", i / 4)),
+                    case: format!("randomStatetest{}_d{}g0v0_Shanghai", i / 4, i % 4),
+                })
+                .collect(),
+            skipped: 0,
+        };
+
+        // When
+        let shards: Vec<_> = (0..4).map(|index| plan.shard(4, index)).collect();
+
+        // Then
+        let mut union: Vec<_> = shards
+            .iter()
+            .flat_map(|shard| shard.cases.clone())
+            .collect();
+        union.sort();
+        let mut expected = plan.cases.clone();
+        expected.sort();
+        assert_eq!(union, expected);
+        for shard in &shards {
+            assert!(!shard.cases.is_empty());
+            assert!(shard.cases.len() < 50);
+        }
+        assert_eq!(plan.shard(4, 2), shards[2]);
+    }
+
+    #[test]
+    fn test_shard_hash_separator() {
+        // Given
+        let case = |file: PathBuf| PlannedCase {
+            file,
+            case: "randomStatetest0_d0g0v0_Shanghai".to_string(),
+        };
+        let joined = case(
+            Path::new("GeneralStateTests")
+                .join("stRandom")
+                .join("randomStatetest0.json"),
+        );
+        let slashed = case(PathBuf::from(
+            "GeneralStateTests/stRandom/randomStatetest0.json",
+        ));
+
+        // When
+        let hash = joined.shard_hash();
+
+        // Then
+        assert_eq!(hash, slashed.shard_hash());
+        assert_eq!(hash, 0xc050_c953_d2f6_7f0f);
+    }
+}
@@ -0,0 +1,266 @@
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+use super::{diff::flatten_fork_map, Category, Filter};
+
+impl Filter {
+    /// Renders the skip list as Markdown, with a table per folder listing each
+    /// pattern, the mapping containing it and its reason. Folders and rows are
+    /// sorted. The entries of the `fork` mapping are listed as `fork/test` patterns,
+    /// the ones of the `extension` and `hash` mappings as file extensions and
+    /// digests, and the forks of `skip_forks` in a leading list.
+    pub fn to_markdown(&self) -> String {
+        let forks = flatten_fork_map(&self.fork);
+        let fork_entries = forks.iter().flat_map(|(folder, entries)| {
+            entries
+                .iter()
+                .map(move |entry| (folder.as_str(), "fork", entry.pattern(), entry.reason()))
+        });
+        let path_entries = [("extension", &self.extension), ("hash", &self.hash)]
+            .into_iter()
+            .flat_map(|(map, folders)| {
+                folders.iter().flat_map(move |(folder, entries)| {
+                    entries
+                        .iter()
+                        .map(move |entry| (folder.as_str(), map, entry.pattern(), entry.reason()))
+                })
+            });
+
+        let mut folders: BTreeMap<&str, Vec<_>> = BTreeMap::new();
+        let entries = self
+            .entries()
+            .map(|entry| {
+                (
+                    entry.folder,
+                    entry.category.name(),
+                    entry.pattern,
+                    entry.reason,
+                )
+            })
+            .chain(fork_entries)
+            .chain(path_entries);
+        for (folder, category, pattern, reason) in entries {
+            folders.entry(folder).or_default().push((
+                pattern,
+                category,
+                reason.unwrap_or_default(),
+            ));
+        }
+
+        let mut markdown = String::new();
+        if !self.skip_forks.is_empty() {
+            let mut forks = self.skip_forks.iter().collect::<Vec<_>>();
+            forks.sort();
+            markdown.push_str("## Skipped forks\n\n");
+            for fork in forks {
+                let _ = writeln!(markdown, "- `{fork}`");
+            }
+        }
+        for (folder, mut rows) in folders {
+            rows.sort();
+            if !markdown.is_empty() {
+                markdown.push('\n');
+            }
+            let _ = writeln!(markdown, "## {folder}\n");
+            markdown.push_str("| Pattern | Category | Reason |\n");
+            markdown.push_str("| --- | --- | --- |\n");
+            for (pattern, category, reason) in rows {
+                let _ = writeln!(
+                    markdown,
+                    "| `{}` | {category} | {} |",
+                    escape(pattern),
+                    escape(reason)
+                );
+            }
+        }
+        markdown
+    }
+}
+
+/// Summarizes the filter with the number of entries and folders of each mapping,
+/// e.g. `Filter { filename: 120 in 40 folders, regex: 15 in 8 folders, testname: 30 in 12 folders }`.
+/// The `filename`, `regex` and `testname` mappings are always listed, the others
+/// and the number of `skip_forks` only if they aren't empty.
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let forks = flatten_fork_map(&self.fork);
+        let counts = Category::ALL
+            .into_iter()
+            .map(|category| (category.name(), self.category_map(category)))
+            .chain([
+                ("fork", &forks),
+                ("extension", &self.extension),
+                ("hash", &self.hash),
+            ])
+            .map(|(name, map)| (name, map.values().map(Vec::len).sum::<usize>(), map.len()))
+            .filter(|(name, entries, _)| {
+                *entries > 0 || ["filename", "regex", "testname"].contains(name)
+            });
+
+        f.write_str("Filter { ")?;
+        for (index, (name, entries, folders)) in counts.enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{name}: {entries} in {folders} folders")?;
+        }
+        if !self.skip_forks.is_empty() {
+            write!(f, ", skip_forks: {}", self.skip_forks.len())?;
+        }
+        f.write_str(" }")
+    }
+}
+
+/// Escapes the pipes, which would otherwise end the table cell.
+fn escape(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_markdown() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stRandom:
+                - randomStatetest1
+                - pattern: randomStatetest0
+                  reason: "flaky gas accounting #123"
+            regex:
+              stBadOpcode:
+                - opc(4D|4E)DiffPlaces
+            testname: {}
+            fork:
+              stRandom:
+                Cancun:
+                  - randomStatetest2
+            extension:
+              stRandom:
+                - yml
+            hash:
+              stBadOpcode:
+                - aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let markdown = filter.to_markdown();
+
+        // Then
+        let expected = r"## stBadOpcode
+
+| Pattern | Category | Reason |
+| --- | --- | --- |
+| `aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa` | hash |  |
+| `opc(4D\|4E)DiffPlaces` | regex |  |
+
+## stRandom
+
+| Pattern | Category | Reason |
+| --- | --- | --- |
+| `Cancun/randomStatetest2` | fork |  |
+| `randomStatetest0` | filename | flaky gas accounting #123 |
+| `randomStatetest1` | filename |  |
+| `yml` | extension |  |
+";
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_to_markdown_skip_forks() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+            skip_forks:
+              - Prague
+              - Cancun
+            ",
+        )
+        .unwrap();
+
+        // When
+        let markdown = filter.to_markdown();
+
+        // Then
+        let expected = r"## Skipped forks
+
+- `Cancun`
+- `Prague`
+
+## stRandom
+
+| Pattern | Category | Reason |
+| --- | --- | --- |
+| `randomStatetest0` | filename |  |
+";
+        assert_eq!(markdown, expected);
+    }
+
+    #[test]
+    fn test_display() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+                - randomStatetest1
+              stBadOpcode:
+                - opc4DDiffPlaces
+            regex:
+              stBadOpcode:
+                - opc(4D|4E)DiffPlaces
+            testname: {}
+            fork:
+              stRandom:
+                Cancun:
+                  - randomStatetest2
+            ",
+        )
+        .unwrap();
+
+        // When
+        let summary = filter.to_string();
+
+        // Then
+        assert_eq!(
+            summary,
+            "Filter { filename: 3 in 2 folders, regex: 1 in 1 folders, testname: 0 in 0 folders, fork: 1 in 1 folders }"
+        );
+    }
+
+    #[test]
+    fn test_display_path_entries() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            extension:
+              stRandom:
+                - yml
+            skip_forks:
+              - Cancun
+              - Prague
+            ",
+        )
+        .unwrap();
+
+        // When
+        let summary = filter.to_string();
+
+        // Then
+        assert_eq!(
+            summary,
+            "Filter { filename: 0 in 0 folders, regex: 0 in 0 folders, testname: 0 in 0 folders, extension: 1 in 1 folders, skip_forks: 2 }"
+        );
+    }
+}
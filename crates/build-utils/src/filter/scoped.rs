@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use super::{Category, Filter, Folder, SkipEntry};
+use crate::path::PathWrapper;
+
+/// View of a filter restricted to a single directory, built by [`Filter::scoped`].
+///
+/// The entries applying to the directory are resolved once, which avoids repeating
+/// the lookups when checking many files of the same directory. A case of a file is
+/// skipped if either [`is_file_skipped`](Self::is_file_skipped) or
+/// [`is_case_skipped`](Self::is_case_skipped) holds. Unlike [`Filter::is_skipped`],
+/// the view only applies the entries of the directory itself and of the `*` folder,
+/// even if the filter cascades, and a `keep` entry only applies to the file or case
+/// name it matches.
+#[derive(Debug, Clone)]
+pub struct ScopedFilter<'a> {
+    filter: &'a Filter,
+    dir_name: Folder,
+    folders: Vec<(Category, &'a Folder, &'a [SkipEntry])>,
+    forks: Option<(&'a Folder, &'a BTreeMap<String, Vec<SkipEntry>>)>,
+    keep: Vec<&'a SkipEntry>,
+}
+
+impl Filter {
+    /// Returns a view of the filter restricted to the directory with the given
+    /// folder key.
+    pub fn scoped(&self, dir_name: &str) -> ScopedFilter<'_> {
+        let folders = Category::ALL
+            .into_iter()
+            .flat_map(|category| {
+                self.matching_folders(self.category_map(category), dir_name)
+                    .map(move |(folder, entries)| (category, folder, entries.as_slice()))
+            })
+            .collect();
+        ScopedFilter {
+            filter: self,
+            dir_name: dir_name.to_string(),
+            folders,
+            forks: self.folder_entries(&self.fork, dir_name),
+            keep: self
+                .matching_folders(&self.keep, dir_name)
+                .flat_map(|(_, entries)| entries)
+                .collect(),
+        }
+    }
+}
+
+impl ScopedFilter<'_> {
+    /// Checks if the file at the given path, which must be in the directory of the
+    /// view, is skipped. The file is matched as by [`Filter::is_skipped`], according
+    /// to the match target, `skip_forks` and the `extension` and `hash` mappings.
+    pub fn is_file_skipped(&self, path: &PathWrapper) -> bool {
+        let file_name = self.filter.match_name(path);
+        !self.is_kept(&file_name)
+            && (self.matches(&file_name, None)
+                || self
+                    .filter
+                    .is_path_skipped(std::slice::from_ref(&self.dir_name), path))
+    }
+
+    /// Checks if the case is skipped by the `testname`, `testname_regex` or
+    /// `fork` mappings.
+    pub fn is_case_skipped(&self, case: &str) -> bool {
+        if self.is_kept(case) {
+            return false;
+        }
+        self.matches("", Some(case))
+            || self.forks.map_or(false, |(folder, forks)| {
+                self.filter
+                    .fork_skip_reason(folder, forks, case)
+                    .map_or(false, |reason| reason.severity().is_skip())
+            })
+    }
+
+    /// Checks if any entry with the `skip` severity matches the file name or the case name.
+    fn matches(&self, file_name: &str, case_name: Option<&str>) -> bool {
+        self.folders
+            .iter()
+            .filter(|(category, _, _)| category.matches_case_name() == case_name.is_some())
+            .any(|(category, folder, entries)| {
+                entries.iter().enumerate().any(|(index, entry)| {
+                    self.filter.entry_severity(folder, entry).is_skip()
+                        && self
+                            .filter
+                            .entry_matches(*category, folder, index, file_name, case_name)
+                })
+            })
+    }
+
+    /// Checks if the name is listed in the `keep` mapping.
+    fn is_kept(&self, name: &str) -> bool {
+        self.keep
+            .iter()
+            .any(|entry| self.filter.names_match(entry.pattern(), name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::tests::{bench, bench_paths};
+    use sha2::{Digest, Sha256};
+    use std::fs;
+
+    const TEST_DATA: &str = "src/test_data/BlockchainTests/GeneralStateTests";
+
+    #[test]
+    fn test_scoped_agrees_with_is_skipped() {
+        // Given
+        let digest = format!(
+            "{:x}",
+            Sha256::digest(
+                fs::read(format!("{TEST_DATA}/stRandom/randomStatetest1.json")).unwrap()
+            )
+        );
+        let filters = [
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stRandom:
+                - randomStatetest0
+              "*":
+                - Callcode.*
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d1g0v0_Shanghai
+            fork:
+              stTransactionTest:
+                Cancun:
+                  - Opcodes_TransactionInit
+            "#
+            .to_string(),
+            r"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls.json
+            regex: {}
+            testname: {}
+            index:
+              stTransactionTest:
+                - d0
+            match_target: file_name
+            "
+            .to_string(),
+            format!(
+                r"
+            filename: {{}}
+            regex: {{}}
+            testname: {{}}
+            extension:
+              stCallCreateCallCodeTest:
+                - json
+            hash:
+              stRandom:
+                - {digest}
+            skip_forks:
+              - stTransactionTest
+            "
+            ),
+            r"
+            filename:
+              stRandom:
+                - randomStatetest0
+            regex: {}
+            testname: {}
+            case_insensitive: true
+            full_match: false
+            folders:
+              stRandom:
+                default_severity: warn
+            "
+            .to_string(),
+        ];
+
+        // Then
+        for filter in filters {
+            let filter: Filter = serde_yaml::from_str(&filter).unwrap();
+            for entry in walkdir::WalkDir::new(TEST_DATA) {
+                let path = entry.unwrap().into_path();
+                if path.extension().map_or(true, |ext| ext != "json") {
+                    continue;
+                }
+                let path = PathWrapper::from(path);
+                let scoped = filter.scoped(&filter.folder_key(&path));
+
+                assert_eq!(
+                    scoped.is_file_skipped(&path),
+                    filter.is_skipped(&path, None),
+                    "{path:?}"
+                );
+                for case in path.read_case_names().unwrap() {
+                    assert_eq!(
+                        scoped.is_file_skipped(&path) || scoped.is_case_skipped(&case),
+                        filter.is_skipped(&path, Some(&case)),
+                        "{case}"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Compares `is_skipped` with a view built once per directory. Run with
+    /// `cargo test -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_scoped() {
+        let filter = Filter::embedded();
+        let paths = bench_paths();
+        let scoped = paths
+            .iter()
+            .map(|path| filter.folder_key(path))
+            .map(|dir_name| (dir_name.clone(), filter.scoped(&dir_name)))
+            .collect::<BTreeMap<_, _>>();
+
+        bench("is_skipped", &paths, |path| filter.is_skipped(path, None));
+        bench("scoped", &paths, |path| {
+            scoped[&filter.folder_key(path)].is_file_skipped(path)
+        });
+    }
+}
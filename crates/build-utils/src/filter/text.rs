@@ -0,0 +1,131 @@
+use std::{fs, path::PathBuf};
+
+use super::{Category, Filter, FilterError};
+
+impl Filter {
+    /// Loads a filter from a plain-text file. Each line holds three whitespace
+    /// separated columns: the folder, the category (`file`, `regex` or `test`)
+    /// and the pattern. Blank lines and `#` comments are ignored.
+    ///
+    /// ```text
+    /// # Too slow
+    /// stMemoryStressTest regex CALL_Bounds.*
+    /// stTransactionTest test Opcodes_TransactionInit_d1g0v0_Shanghai
+    /// ```
+    ///
+    /// The filter goes through the same preparation as [`Filter::load_file`]:
+    /// `${VAR}` placeholders are expanded before it is validated.
+    pub fn load_text(path: &str) -> Result<Self, eyre::Error> {
+        let content = fs::read_to_string(path)?;
+        let filter = Self::parse_text(&content)
+            .map_err(|err| eyre::eyre!("Error in filter file {path}: {err}"))?;
+        let mut filter = Self::prepare(filter).map_err(|error| FilterError::Validation {
+            path: PathBuf::from(path),
+            error: Box::new(error),
+        })?;
+        filter.source = Some(PathBuf::from(path));
+        Ok(filter)
+    }
+
+    /// Parses the content of a plain-text filter file.
+    fn parse_text(content: &str) -> Result<Self, eyre::Error> {
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| {
+                let line = line.split('#').next().unwrap_or_default().trim();
+                (!line.is_empty()).then_some((index + 1, line))
+            })
+            .map(|(line_number, line)| {
+                let columns = line.split_whitespace().collect::<Vec<_>>();
+                let [folder, category, pattern] = columns[..] else {
+                    eyre::bail!("line {line_number}: expected `folder category pattern`, got `{line}`");
+                };
+                let category = match category {
+                    "file" => Category::Filename,
+                    "regex" => Category::Regex,
+                    "test" => Category::TestName,
+                    _ => eyre::bail!(
+                        "line {line_number}: unknown category `{category}`, expected one of `file`, `regex` or `test`"
+                    ),
+                };
+                Ok((folder.to_string(), category, pattern.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(|entries| entries.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path::PathWrapper;
+    use std::io::Write;
+
+    #[test]
+    fn test_load_text() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        writeln!(
+            file,
+            "# Skipped tests\n\nstCallCreateCallCodeTest file Call1024PreCalls\nstRandom regex randomStatetest0.* # flaky\n  stTransactionTest  test  Opcodes_TransactionInit_d1g0v0_Shanghai"
+        )
+        .unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        let filter = Filter::load_text(path).unwrap();
+
+        // Then
+        let path = |path: &str| PathWrapper::from(PathBuf::from(path));
+        assert!(filter.is_skipped(
+            &path("GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json"),
+            None
+        ));
+        assert!(filter.is_skipped(
+            &path("GeneralStateTests/stRandom/randomStatetest0.json"),
+            None
+        ));
+        assert!(!filter.is_skipped(
+            &path("GeneralStateTests/stRandom/randomStatetest1.json"),
+            None
+        ));
+        let transaction = path("GeneralStateTests/stTransactionTest/Opcodes_TransactionInit.json");
+        assert!(filter.is_skipped(
+            &transaction,
+            Some("Opcodes_TransactionInit_d1g0v0_Shanghai")
+        ));
+        assert!(!filter.is_skipped(
+            &transaction,
+            Some("Opcodes_TransactionInit_d2g0v0_Shanghai")
+        ));
+    }
+
+    #[test]
+    fn test_load_text_prepares_filter() {
+        // Given
+        let mut file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        writeln!(
+            file,
+            "stRandom file ${{EF_TESTS_UNSET_VAR}}\nstRandom regex randomStatetest0.*"
+        )
+        .unwrap();
+        let path = file.path().to_str().unwrap();
+
+        // When
+        let filter = Filter::load_text(path).unwrap();
+
+        // Then
+        assert!(filter.filename["stRandom"].is_empty());
+        assert_eq!(filter.source(), Some(file.path()));
+    }
+
+    #[test]
+    fn test_parse_text_unknown_category() {
+        // When
+        let err = Filter::parse_text("stRandom glob random*").unwrap_err();
+
+        // Then
+        assert!(err.to_string().contains("line 1: unknown category `glob`"));
+    }
+}
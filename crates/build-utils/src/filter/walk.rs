@@ -0,0 +1,630 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use super::{Category, Filter, Folder};
+use crate::{constants::WILDCARD_FOLDER, dir_reader::DirReader, path::PathWrapper};
+
+/// Entry of the filter which doesn't match any test file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleEntry {
+    /// The folder containing the entry
+    pub folder: Folder,
+    /// The pattern of the entry
+    pub pattern: String,
+    /// The mapping containing the entry
+    pub category: Category,
+}
+
+/// Statistics about the tests skipped by a filter
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SkipStats {
+    /// Number of files for which all cases are skipped
+    pub files_skipped: usize,
+    /// Number of cases skipped
+    pub cases_skipped: usize,
+    /// Number of cases skipped for each folder
+    pub per_folder: BTreeMap<Folder, usize>,
+    /// Number of files with at least one case that runs
+    pub files_kept: usize,
+    /// Number of cases that run
+    pub cases_kept: usize,
+    /// Files whose cases couldn't be read, which are counted neither as skipped
+    /// nor as kept
+    pub unreadable_files: Vec<PathBuf>,
+}
+
+impl Filter {
+    /// Walks the test files under `test_root` and counts the files and cases
+    /// that the filter skips or keeps. Cases excluded by the `only` mapping are
+    /// counted as skipped. The `progress` callback is invoked for each visited file.
+    pub fn count_skipped(
+        &self,
+        test_root: &Path,
+        progress: Option<&mut dyn FnMut(&Path)>,
+    ) -> SkipStats {
+        let mut stats = SkipStats::default();
+
+        for path in test_files(test_root, progress) {
+            let Ok(case_names) = path.read_case_names() else {
+                stats.unreadable_files.push(path.into());
+                continue;
+            };
+            let skipped = case_names
+                .iter()
+                .filter(|case_name| self.is_excluded(&path, Some(case_name)))
+                .count();
+
+            let is_file_skipped = if case_names.is_empty() {
+                self.is_excluded(&path, None)
+            } else {
+                skipped == case_names.len()
+            };
+            if is_file_skipped {
+                stats.files_skipped += 1;
+            } else {
+                stats.files_kept += 1;
+            }
+            stats.cases_kept += case_names.len() - skipped;
+            if skipped > 0 {
+                stats.cases_skipped += skipped;
+                *stats.per_folder.entry(self.folder_key(&path)).or_default() += skipped;
+            }
+        }
+
+        stats
+    }
+
+    /// Walks the test files under `test_root` and yields each file with at least
+    /// one case that is neither skipped nor excluded by the `only` mapping, along
+    /// with the names of those cases.
+    pub fn runnable<'a>(
+        &'a self,
+        test_root: &Path,
+    ) -> impl Iterator<Item = (PathWrapper, Vec<String>)> + 'a {
+        test_files(test_root, None).filter_map(move |path| {
+            let case_names = path
+                .read_case_names()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|case_name| !self.is_excluded(&path, Some(case_name)))
+                .collect::<Vec<_>>();
+            (!case_names.is_empty()).then_some((path, case_names))
+        })
+    }
+
+    /// Walks the test files under `test_root` and returns the skipped cases, as
+    /// pairs of the `/` separated path of the file relative to `test_root` and the
+    /// case name. Files without cases are listed with no case name if they are
+    /// skipped. The `only` mapping isn't considered.
+    pub fn skipped_under(&self, test_root: &Path) -> BTreeSet<(String, Option<String>)> {
+        let mut skipped = BTreeSet::new();
+        for path in test_files(test_root, None) {
+            let relative_path = path
+                .relative_to(test_root)
+                .unwrap_or_else(|| path.clone())
+                .as_ref()
+                .iter()
+                .map(|component| component.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let case_names = path.read_case_names().unwrap_or_default();
+            if case_names.is_empty() {
+                if self.is_skipped(&path, None) {
+                    skipped.insert((relative_path, None));
+                }
+                continue;
+            }
+            for case_name in case_names {
+                if self.is_skipped(&path, Some(&case_name)) {
+                    skipped.insert((relative_path.clone(), Some(case_name)));
+                }
+            }
+        }
+        skipped
+    }
+
+    /// Walks the test files under `test_root` and returns the entries of the
+    /// mappings of [`Category::ALL`] which didn't match any file or case. The `progress` callback is invoked
+    /// for each visited file.
+    pub fn find_stale(
+        &self,
+        test_root: &Path,
+        progress: Option<&mut dyn FnMut(&Path)>,
+    ) -> Vec<StaleEntry> {
+        let mut matched = BTreeSet::new();
+
+        for path in test_files(test_root, progress) {
+            let dir_names = self.folder_keys(&path);
+            let file_name = self.match_name(&path);
+            let case_names = path.read_case_names().unwrap_or_default();
+
+            for category in Category::ALL {
+                let folders = dir_names.iter().flat_map(|dir_name| {
+                    self.matching_folders(self.category_map(category), dir_name)
+                });
+                for (folder, entries) in folders {
+                    for index in 0..entries.len() {
+                        if matched.contains(&(category, folder, index)) {
+                            continue;
+                        }
+                        let is_match = if category.matches_case_name() {
+                            case_names.iter().any(|case_name| {
+                                self.entry_matches(
+                                    category,
+                                    folder,
+                                    index,
+                                    &file_name,
+                                    Some(case_name),
+                                )
+                            })
+                        } else {
+                            self.entry_matches(category, folder, index, &file_name, None)
+                        };
+                        if is_match {
+                            matched.insert((category, folder, index));
+                        }
+                    }
+                }
+            }
+        }
+
+        Category::ALL
+            .into_iter()
+            .flat_map(|category| {
+                self.category_map(category)
+                    .iter()
+                    .flat_map(move |(folder, entries)| {
+                        entries
+                            .iter()
+                            .enumerate()
+                            .map(move |(index, entry)| (category, folder, index, entry))
+                    })
+            })
+            .filter(|(category, folder, index, _)| !matched.contains(&(*category, *folder, *index)))
+            .map(|(category, folder, _, entry)| StaleEntry {
+                folder: folder.clone(),
+                pattern: entry.pattern().to_string(),
+                category,
+            })
+            .collect()
+    }
+
+    /// Returns the JSON test files under the given directory which are skipped as a
+    /// whole, i.e. for which [`Filter::is_skipped`] returns true without a case name,
+    /// sorted by path.
+    pub fn matching_files(&self, folder_path: &Path) -> Vec<PathWrapper> {
+        let mut files = test_files(folder_path, None)
+            .filter(|path| self.is_skipped(path, None))
+            .collect::<Vec<_>>();
+        files.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        files
+    }
+
+    /// Walks the test files under `test_root` and returns the keys of the folders
+    /// in which every file is skipped, i.e. all of its cases are. The `only`
+    /// mapping isn't considered.
+    pub fn fully_skipped_folders(&self, test_root: &Path) -> Vec<Folder> {
+        let mut folders = BTreeMap::<Folder, bool>::new();
+        for path in test_files(test_root, None) {
+            let case_names = path.read_case_names().unwrap_or_default();
+            let is_file_skipped = if case_names.is_empty() {
+                self.is_skipped(&path, None)
+            } else {
+                self.partition_cases(&path, &case_names).1.is_empty()
+            };
+            *folders.entry(self.folder_key(&path)).or_insert(true) &= is_file_skipped;
+        }
+
+        folders
+            .into_iter()
+            .filter_map(|(folder, is_skipped)| is_skipped.then_some(folder))
+            .collect()
+    }
+
+    /// Walks the test files under `test_root` and returns the folder keys of the
+    /// filter which don't match any directory containing test files, or any of their
+    /// ancestors if the filter cascades. The `*` folder is always considered valid.
+    pub fn find_unknown_folders(&self, test_root: &Path) -> Vec<Folder> {
+        let normalize = |folder: &str| {
+            if self.case_insensitive {
+                folder.to_lowercase()
+            } else {
+                folder.to_string()
+            }
+        };
+        let known = test_files(test_root, None)
+            .flat_map(|path| self.folder_keys(&path))
+            .map(|folder| normalize(&folder))
+            .collect::<BTreeSet<_>>();
+
+        let folders = Category::ALL
+            .into_iter()
+            .flat_map(|category| self.category_map(category).keys())
+            .chain(self.fork.keys())
+            .chain(self.only.keys())
+            .chain(self.keep.keys())
+            .chain(self.extension.keys())
+            .chain(self.hash.keys())
+            .chain(self.folders.keys())
+            .filter(|folder| folder.as_str() != WILDCARD_FOLDER)
+            .filter(|folder| !known.contains(&normalize(folder)))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        folders.into_iter().collect()
+    }
+}
+
+/// Returns the JSON test files under the given directory, invoking the
+/// `progress` callback for each of them.
+pub(super) fn test_files<'a>(
+    test_root: &Path,
+    mut progress: Option<&'a mut dyn FnMut(&Path)>,
+) -> impl Iterator<Item = PathWrapper> + 'a {
+    DirReader::walk_dir(test_root.to_path_buf().into())
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "json"))
+        .inspect(move |entry| {
+            if let Some(progress) = progress.as_mut() {
+                progress(entry.path());
+            }
+        })
+        .map(|entry| entry.path().to_path_buf().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    const TEST_DATA: &str = "src/test_data/BlockchainTests/GeneralStateTests";
+
+    #[test]
+    fn test_count_skipped() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stRandom:
+                - randomStatetest0
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d1g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let stats = filter.count_skipped(Path::new(TEST_DATA), None);
+
+        // Then
+        assert_eq!(stats.files_skipped, 2);
+        assert_eq!(stats.cases_skipped, 4);
+        assert_eq!(
+            stats.per_folder,
+            BTreeMap::from([
+                ("stCallCreateCallCodeTest".to_string(), 2),
+                ("stRandom".to_string(), 1),
+                ("stTransactionTest".to_string(), 1),
+            ])
+        );
+        assert_eq!(stats.files_kept, 3);
+        assert_eq!(stats.cases_kept, 4);
+        assert!(stats.unreadable_files.is_empty());
+    }
+
+    #[test]
+    fn test_count_skipped_unreadable_file() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("stRandom");
+        fs::create_dir(&folder).unwrap();
+        fs::write(folder.join("randomStatetest0.json"), "not json").unwrap();
+        fs::write(folder.join("randomStatetest1.json"), r#"{"case": {}}"#).unwrap();
+
+        // When
+        let stats = Filter::default().count_skipped(dir.path(), None);
+
+        // Then
+        assert_eq!(
+            stats.unreadable_files,
+            vec![folder.join("randomStatetest0.json")]
+        );
+        assert_eq!(stats.files_kept, 1);
+        assert_eq!(stats.cases_kept, 1);
+        assert_eq!(stats.files_skipped, 0);
+    }
+
+    #[test]
+    fn test_runnable() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stRandom:
+                - randomStatetest0
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d1g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let mut runnable = filter
+            .runnable(Path::new(TEST_DATA))
+            .map(|(path, cases)| (path.file_stem_to_string(), cases))
+            .collect::<Vec<_>>();
+        runnable.sort();
+
+        // Then
+        let expected = vec![
+            (
+                "Callcode1024BalanceTooLow".to_string(),
+                vec!["Callcode1024BalanceTooLow_d0g0v0_Shanghai".to_string()],
+            ),
+            (
+                "Opcodes_TransactionInit".to_string(),
+                vec![
+                    "Opcodes_TransactionInit_d0g0v0_Cancun".to_string(),
+                    "Opcodes_TransactionInit_d0g0v0_Shanghai".to_string(),
+                ],
+            ),
+            (
+                "randomStatetest1".to_string(),
+                vec!["randomStatetest1_d0g0v0_Shanghai".to_string()],
+            ),
+        ];
+        assert_eq!(runnable, expected);
+    }
+
+    #[test]
+    fn test_skipped_under() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex: {}
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d1g0v0_Shanghai
+            fork:
+              stRandom:
+                Shanghai:
+                  - randomStatetest1
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let skipped = filter.skipped_under(Path::new(TEST_DATA));
+
+        // Then
+        let case = |path: &str, case_name: &str| (path.to_string(), Some(case_name.to_string()));
+        let expected = BTreeSet::from([
+            case(
+                "stCallCreateCallCodeTest/Call1024PreCalls.json",
+                "Call1024PreCalls_d0g0v0_Shanghai",
+            ),
+            case(
+                "stCallCreateCallCodeTest/Call1024PreCalls.json",
+                "Call1024PreCalls_d0g1v0_Shanghai",
+            ),
+            case(
+                "stRandom/randomStatetest1.json",
+                "randomStatetest1_d0g0v0_Shanghai",
+            ),
+            case(
+                "stTransactionTest/Opcodes_TransactionInit.json",
+                "Opcodes_TransactionInit_d1g0v0_Shanghai",
+            ),
+        ]);
+        assert_eq!(skipped, expected);
+    }
+
+    #[test]
+    fn test_fully_skipped_folders() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              stCallCreateCallCodeTest:
+                - Callcode.*
+              stRandom:
+                - randomStatetest0
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d0g0v0_Shanghai
+                - Opcodes_TransactionInit_d1g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let folders = filter.fully_skipped_folders(Path::new(TEST_DATA));
+
+        // Then
+        assert_eq!(folders, vec!["stCallCreateCallCodeTest".to_string()]);
+    }
+
+    #[test]
+    fn test_matching_files() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+              stRandom:
+                - randomStatetest0
+            regex: {}
+            testname: {}
+            "#,
+        )
+        .unwrap();
+        let folder = Path::new(TEST_DATA).join("stCallCreateCallCodeTest");
+
+        // When
+        let files = filter.matching_files(&folder);
+
+        // Then
+        let files = files.into_iter().map(PathBuf::from).collect::<Vec<_>>();
+        assert_eq!(files, vec![folder.join("Call1024PreCalls.json")]);
+    }
+
+    #[test]
+    fn test_find_stale() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+                - Call1024Deleted
+              stDeleted:
+                - deletedTest
+            regex:
+              stRandom:
+                - randomStatetest.*
+                - randomDeleted.*
+            testname:
+              stTransactionTest:
+                - Opcodes_TransactionInit_d1g0v0_Shanghai
+                - Opcodes_TransactionInit_d2g0v0_Shanghai
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let stale = filter.find_stale(Path::new(TEST_DATA), None);
+
+        // Then
+        let expected = vec![
+            StaleEntry {
+                folder: "stCallCreateCallCodeTest".to_string(),
+                pattern: "Call1024Deleted".to_string(),
+                category: Category::Filename,
+            },
+            StaleEntry {
+                folder: "stDeleted".to_string(),
+                pattern: "deletedTest".to_string(),
+                category: Category::Filename,
+            },
+            StaleEntry {
+                folder: "stRandom".to_string(),
+                pattern: "randomDeleted.*".to_string(),
+                category: Category::Regex,
+            },
+            StaleEntry {
+                folder: "stTransactionTest".to_string(),
+                pattern: "Opcodes_TransactionInit_d2g0v0_Shanghai".to_string(),
+                category: Category::TestName,
+            },
+        ];
+        assert_eq!(stale, expected);
+    }
+
+    #[test]
+    fn test_count_skipped_progress() {
+        // Given
+        let filter = Filter::default();
+        let mut visited = Vec::new();
+
+        // When
+        filter.count_skipped(
+            Path::new(TEST_DATA),
+            Some(&mut |path: &Path| visited.push(path.to_path_buf())),
+        );
+
+        // Then
+        let mut expected = test_files(Path::new(TEST_DATA), None)
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+        expected.sort();
+        visited.sort();
+        assert_eq!(visited.len(), 5);
+        assert_eq!(visited, expected);
+    }
+
+    #[test]
+    fn test_find_unknown_folders() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r#"
+            filename:
+              stCallCreateCallCodeTest:
+                - Call1024PreCalls
+              stCallcreateCallCodeTest:
+                - Call1024PreCalls
+            regex:
+              "*":
+                - .*_benchmark
+            testname: {}
+            extension:
+              stTypoFolder:
+                - yml
+            hash:
+              stTypo2:
+                - aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa
+            "#,
+        )
+        .unwrap();
+
+        // When
+        let unknown = filter.find_unknown_folders(Path::new(TEST_DATA));
+
+        // Then
+        assert_eq!(
+            unknown,
+            vec![
+                "stCallcreateCallCodeTest".to_string(),
+                "stTypo2".to_string(),
+                "stTypoFolder".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_cascading_ancestor_key() {
+        // Given
+        let filter: Filter = serde_yaml::from_str(
+            r"
+            filename: {}
+            regex:
+              GeneralStateTests:
+                - randomStatetest0
+                - notATest
+            testname: {}
+            cascade: true
+            ",
+        )
+        .unwrap();
+
+        // When
+        let unknown = filter.find_unknown_folders(Path::new(TEST_DATA));
+        let stale = filter.find_stale(Path::new(TEST_DATA), None);
+
+        // Then
+        assert!(unknown.is_empty());
+        assert_eq!(
+            stale,
+            vec![StaleEntry {
+                folder: "GeneralStateTests".to_string(),
+                pattern: "notATest".to_string(),
+                category: Category::Regex,
+            }]
+        );
+    }
+}
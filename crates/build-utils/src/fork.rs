@@ -0,0 +1,131 @@
+use std::fmt;
+
+/// Fork of the Ethereum protocol, as named in the EF test case names
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fork {
+    /// The Frontier fork
+    Frontier,
+    /// The Homestead fork
+    Homestead,
+    /// The Tangerine Whistle fork, named `EIP150` in the case names
+    Eip150,
+    /// The Spurious Dragon fork, named `EIP158` in the case names
+    Eip158,
+    /// The Byzantium fork
+    Byzantium,
+    /// The Constantinople fork
+    Constantinople,
+    /// The Petersburg fork, named `ConstantinopleFix` in the case names
+    ConstantinopleFix,
+    /// The Istanbul fork
+    Istanbul,
+    /// The Berlin fork
+    Berlin,
+    /// The London fork
+    London,
+    /// The Paris fork, named `Merge` or `Paris` in the case names
+    Merge,
+    /// The Shanghai fork
+    Shanghai,
+    /// The Cancun fork
+    Cancun,
+    /// The Prague fork
+    Prague,
+    /// Fork unknown to this crate, e.g. a newly added one
+    Other(String),
+}
+
+impl Fork {
+    /// Returns the name of the fork, as used in the test case names
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Frontier => "Frontier",
+            Self::Homestead => "Homestead",
+            Self::Eip150 => "EIP150",
+            Self::Eip158 => "EIP158",
+            Self::Byzantium => "Byzantium",
+            Self::Constantinople => "Constantinople",
+            Self::ConstantinopleFix => "ConstantinopleFix",
+            Self::Istanbul => "Istanbul",
+            Self::Berlin => "Berlin",
+            Self::London => "London",
+            Self::Merge => "Merge",
+            Self::Shanghai => "Shanghai",
+            Self::Cancun => "Cancun",
+            Self::Prague => "Prague",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+impl From<&str> for Fork {
+    fn from(name: &str) -> Self {
+        match name {
+            "Frontier" => Self::Frontier,
+            "Homestead" => Self::Homestead,
+            "EIP150" => Self::Eip150,
+            "EIP158" => Self::Eip158,
+            "Byzantium" => Self::Byzantium,
+            "Constantinople" => Self::Constantinople,
+            "ConstantinopleFix" => Self::ConstantinopleFix,
+            "Istanbul" => Self::Istanbul,
+            "Berlin" => Self::Berlin,
+            "London" => Self::London,
+            "Merge" | "Paris" => Self::Merge,
+            "Shanghai" => Self::Shanghai,
+            "Cancun" => Self::Cancun,
+            "Prague" => Self::Prague,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Fork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the fork from the trailing `_<Fork>` token of the case name.
+/// Returns `None` if the case name has no such token, the fork names
+/// always starting with an uppercase letter.
+///
+/// Input: Opcodes_TransactionInit_d111g0v0_Shanghai
+/// Output: Some(Fork::Shanghai)
+pub fn parse_fork(case_name: &str) -> Option<Fork> {
+    let (_, fork) = case_name.rsplit_once('_')?;
+    if !fork.starts_with(|c: char| c.is_ascii_uppercase()) {
+        return None;
+    }
+    Some(Fork::from(fork))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fork_known() {
+        assert_eq!(
+            parse_fork("Opcodes_TransactionInit_d111g0v0_Shanghai"),
+            Some(Fork::Shanghai)
+        );
+        assert_eq!(
+            parse_fork("Opcodes_TransactionInit_d0g0v0_Cancun"),
+            Some(Fork::Cancun)
+        );
+    }
+
+    #[test]
+    fn test_parse_fork_unknown() {
+        let fork = parse_fork("Opcodes_TransactionInit_d0g0v0_Osaka");
+        assert_eq!(fork, Some(Fork::Other("Osaka".to_string())));
+        assert_eq!(fork.unwrap().to_string(), "Osaka");
+    }
+
+    #[test]
+    fn test_parse_fork_no_suffix() {
+        assert_eq!(parse_fork("Opcodes_TransactionInit_d0g0v0"), None);
+        assert_eq!(parse_fork("randomStatetest0"), None);
+    }
+}
@@ -1,8 +1,10 @@
 //! Procedural macros.
+pub mod case;
 mod constants;
 mod content_reader;
 pub mod converter;
 pub mod dir_reader;
 pub mod filter;
-mod path;
+pub mod fork;
+pub mod path;
 mod utils;
@@ -0,0 +1,113 @@
+//! Command line tool to query the tests filter.
+use std::{path::PathBuf, process::ExitCode};
+
+use build_utils::{
+    filter::{ChangeSummary, Classification, DiffReport, Filter, SKIP_FILE_ENV},
+    path::PathWrapper,
+};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+
+#[derive(Parser)]
+#[command(about = "Query the blockchain tests filter")]
+struct Cli {
+    /// Path to the filter file
+    #[arg(long, env = SKIP_FILE_ENV, default_value = "blockchain-tests-skip.yml")]
+    filter: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Checks if the test file, or one of its cases, is skipped by the filter.
+    /// Exits with a non-zero code if it is. Tests matching an entry with the `warn`
+    /// severity run and exit successfully.
+    Check {
+        /// Path to the test file
+        path: PathBuf,
+        /// Name of the test case
+        case: Option<String>,
+    },
+    /// Lists the folders of the filter which don't match any directory of the
+    /// tests. Exits with a non-zero code if there is any.
+    Folders {
+        /// Path to the root directory of the tests
+        test_root: PathBuf,
+    },
+    /// Lists the patterns added and removed by another filter file compared to
+    /// the filter.
+    Diff {
+        /// Path to the other filter file
+        other: String,
+        /// Prints the patterns and their counts as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Output of the `diff` command in JSON mode
+#[derive(Serialize)]
+struct DiffOutput {
+    report: DiffReport,
+    summary: ChangeSummary,
+}
+
+fn main() -> Result<ExitCode, eyre::Error> {
+    let cli = Cli::parse();
+    let filter = Filter::load_file(&cli.filter)?;
+
+    match cli.command {
+        Command::Check { path, case } => {
+            let path = PathWrapper::from(path);
+            let warning = match filter.classify(&path, case.as_deref()) {
+                Classification::Skip(reason) => {
+                    println!("skipped: {reason:?}");
+                    return Ok(ExitCode::FAILURE);
+                }
+                Classification::WarnOnly(reason) => Some(reason),
+                Classification::Run => None,
+            };
+            if !filter.is_included(&path, case.as_deref()) {
+                println!("skipped: not included by the `only` mapping");
+                return Ok(ExitCode::FAILURE);
+            }
+            match warning {
+                Some(reason) => println!("runs, failures reported as warnings: {reason:?}"),
+                None => println!("not skipped"),
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Folders { test_root } => {
+            let unknown = filter.find_unknown_folders(&test_root);
+            for folder in &unknown {
+                println!("unknown folder: {folder}");
+            }
+            if unknown.is_empty() {
+                Ok(ExitCode::SUCCESS)
+            } else {
+                Ok(ExitCode::FAILURE)
+            }
+        }
+        Command::Diff { other, json } => {
+            let other = Filter::load_file(&other)?;
+            let report = filter.diff_report(&other);
+            let summary = filter.change_summary(&other);
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string(&DiffOutput { report, summary })?
+                );
+                return Ok(ExitCode::SUCCESS);
+            }
+            for (folder, pattern) in &report.added {
+                println!("+ {folder}: {pattern}");
+            }
+            for (folder, pattern) in &report.removed {
+                println!("- {folder}: {pattern}");
+            }
+            println!("net +{} / -{} patterns", summary.added, summary.removed);
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
@@ -1,4 +1,44 @@
-use std::{io::Read, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::Infallible,
+    fmt,
+    io::Read,
+    path::{Path, PathBuf, MAIN_SEPARATOR},
+    str::FromStr,
+};
+
+use serde::de::IgnoredAny;
+
+use crate::{
+    constants::{BLOCKCHAIN_TESTS, ROOT, STATE_TESTS},
+    fork::{parse_fork, Fork},
+};
+
+/// Error returned when a path doesn't have the expected shape
+#[derive(Debug, thiserror::Error)]
+pub enum PathError {
+    /// The path has no file stem, e.g. `..` or an empty path
+    #[error("path {} has no file stem", .path.display())]
+    MissingFileStem {
+        /// The offending path
+        path: PathBuf,
+    },
+    /// The file stem of the path isn't valid UTF-8
+    #[error("file stem of path {} is not valid UTF-8", .path.display())]
+    NonUtf8FileStem {
+        /// The offending path
+        path: PathBuf,
+    },
+}
+
+/// Kind of the tests contained in a test file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestKind {
+    /// Test file from the `BlockchainTests` folder
+    BlockchainTest,
+    /// Test file from the `GeneralStateTests` or `StateTests` folder
+    StateTest,
+}
 
 #[derive(Clone, Debug, Default)]
 pub struct PathWrapper(PathBuf);
@@ -15,6 +55,32 @@ impl From<PathWrapper> for PathBuf {
     }
 }
 
+impl AsRef<Path> for PathWrapper {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl FromStr for PathWrapper {
+    type Err = Infallible;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Ok(Self(PathBuf::from(path)))
+    }
+}
+
+/// Displays the path with `/` separators on every platform.
+impl fmt::Display for PathWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.0.to_string_lossy();
+        if MAIN_SEPARATOR == '/' {
+            f.write_str(&path)
+        } else {
+            f.write_str(&path.replace(MAIN_SEPARATOR, "/"))
+        }
+    }
+}
+
 impl PathWrapper {
     pub fn read_file_to_string(&self) -> std::io::Result<String> {
         let mut content = String::new();
@@ -31,6 +97,12 @@ impl PathWrapper {
         )
     }
 
+    /// Returns the file stem of the path, converting non UTF-8 characters lossily.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the path has no file stem, see
+    /// [`try_file_stem_to_string`](Self::try_file_stem_to_string) for a fallible variant.
     pub fn file_stem_to_string(&self) -> String {
         self.0
             .file_stem()
@@ -38,4 +110,281 @@ impl PathWrapper {
             .to_string_lossy()
             .to_string()
     }
+
+    /// Returns the file stem of the path, or an error identifying the path if it
+    /// has no file stem or if the file stem isn't valid UTF-8.
+    pub fn try_file_stem_to_string(&self) -> Result<String, PathError> {
+        let stem = self
+            .0
+            .file_stem()
+            .ok_or_else(|| PathError::MissingFileStem {
+                path: self.0.clone(),
+            })?;
+        stem.to_str()
+            .map(ToString::to_string)
+            .ok_or_else(|| PathError::NonUtf8FileStem {
+                path: self.0.clone(),
+            })
+    }
+
+    /// Returns the kind of the test file, determined from the components of the path.
+    /// A `BlockchainTests` component takes precedence, as the blockchain tests contain
+    /// a `GeneralStateTests` folder.
+    pub fn test_kind(&self) -> Option<TestKind> {
+        let has_component = |name: &str| self.0.components().any(|c| c.as_os_str() == name);
+        if has_component(BLOCKCHAIN_TESTS) {
+            Some(TestKind::BlockchainTest)
+        } else if has_component(ROOT) || has_component(STATE_TESTS) {
+            Some(TestKind::StateTest)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the path relative to `base`, or `None` if `base` is not a prefix of the path.
+    pub fn relative_to(&self, base: &Path) -> Option<Self> {
+        self.0
+            .strip_prefix(base)
+            .ok()
+            .map(|path| Self(path.to_path_buf()))
+    }
+
+    /// Returns the `/` separated components following the first component equal to
+    /// `marker`, e.g. `GeneralStateTests/stRandom` after `BlockchainTests`, or `None`
+    /// if no component is equal to `marker`.
+    pub fn components_after(&self, marker: &str) -> Option<String> {
+        let mut components = self.0.iter();
+        components.by_ref().find(|component| *component == marker)?;
+        Some(
+            components
+                .map(|component| component.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        )
+    }
+
+    /// Returns the names of the test cases, i.e. the top-level keys of the JSON test file.
+    pub fn read_case_names(&self) -> Result<Vec<String>, eyre::Error> {
+        let content = self.read_file_to_string()?;
+        let cases: BTreeMap<String, IgnoredAny> =
+            serde_json::from_str(&content).map_err(|err| {
+                eyre::eyre!(
+                    "Error reading the test cases of {}, expected a JSON object: {err}",
+                    self.0.display()
+                )
+            })?;
+        Ok(cases.into_keys().collect())
+    }
+
+    /// Returns the distinct forks of the test cases of the JSON test file. Cases
+    /// without a fork suffix are ignored.
+    pub fn forks(&self) -> Result<BTreeSet<Fork>, eyre::Error> {
+        Ok(self
+            .read_case_names()?
+            .iter()
+            .filter_map(|case_name| parse_fork(case_name))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_file_stem_to_string() {
+        // Given
+        let path = PathWrapper::from(PathBuf::from(
+            "GeneralStateTests/stRandom/randomStatetest0.json",
+        ));
+
+        // When
+        let stem = path.try_file_stem_to_string().unwrap();
+
+        // Then
+        assert_eq!(stem, "randomStatetest0");
+    }
+
+    #[test]
+    fn test_from_str_display_round_trip() {
+        // Given
+        let path = "GeneralStateTests/stRandom/randomStatetest0.json";
+
+        // When
+        let wrapper: PathWrapper = path.parse().unwrap();
+
+        // Then
+        assert_eq!(wrapper.as_ref(), Path::new(path));
+        assert_eq!(wrapper.to_string(), path);
+        assert_eq!(
+            PathWrapper::from(PathBuf::from("GeneralStateTests").join("stRandom")).to_string(),
+            "GeneralStateTests/stRandom"
+        );
+    }
+
+    #[test]
+    fn test_try_file_stem_to_string_no_stem() {
+        // Given
+        let path = PathWrapper::from(PathBuf::from("GeneralStateTests/.."));
+
+        // When
+        let err = path.try_file_stem_to_string().unwrap_err();
+
+        // Then
+        assert!(matches!(err, PathError::MissingFileStem { .. }));
+        assert!(err.to_string().contains("GeneralStateTests/.."));
+    }
+
+    #[test]
+    fn test_test_kind() {
+        for (path, expected) in [
+            (
+                "ethereum-tests/BlockchainTests/GeneralStateTests/stRandom/randomStatetest0.json",
+                Some(TestKind::BlockchainTest),
+            ),
+            (
+                "ethereum-tests/BlockchainTests/ValidBlocks/bcEIP1559/baseFee.json",
+                Some(TestKind::BlockchainTest),
+            ),
+            (
+                "ethereum-tests/GeneralStateTests/stRandom/randomStatetest0.json",
+                Some(TestKind::StateTest),
+            ),
+            (
+                "fixtures/StateTests/stRandom/randomStatetest0.json",
+                Some(TestKind::StateTest),
+            ),
+            (
+                "ethereum-tests/TransactionTests/ttNonce/TransactionWithHighNonce32.json",
+                None,
+            ),
+        ] {
+            let path = PathWrapper::from(PathBuf::from(path));
+            assert_eq!(path.test_kind(), expected, "{path:?}");
+        }
+    }
+
+    #[test]
+    fn test_relative_to() {
+        // Given
+        let path = PathWrapper::from(PathBuf::from(
+            "ethereum-tests/BlockchainTests/GeneralStateTests/stRandom/randomStatetest0.json",
+        ));
+
+        // When
+        let relative = path.relative_to(Path::new("ethereum-tests/BlockchainTests"));
+
+        // Then
+        assert_eq!(
+            relative.map(PathBuf::from),
+            Some(PathBuf::from(
+                "GeneralStateTests/stRandom/randomStatetest0.json"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_relative_to_not_a_prefix() {
+        // Given
+        let path = PathWrapper::from(PathBuf::from(
+            "ethereum-tests/BlockchainTests/GeneralStateTests/stRandom/randomStatetest0.json",
+        ));
+
+        // When
+        let relative = path.relative_to(Path::new("ethereum-tests/GeneralStateTests"));
+
+        // Then
+        assert!(relative.is_none());
+    }
+
+    #[test]
+    fn test_components_after() {
+        // Given
+        let path = PathWrapper::from(PathBuf::from(
+            "ethereum-tests/BlockchainTests/GeneralStateTests/stRandom/randomStatetest0.json",
+        ));
+
+        // When
+        let components = path.components_after(BLOCKCHAIN_TESTS);
+
+        // Then
+        assert_eq!(
+            components.as_deref(),
+            Some("GeneralStateTests/stRandom/randomStatetest0.json")
+        );
+    }
+
+    #[test]
+    fn test_components_after_missing_marker() {
+        // Given
+        let path = PathWrapper::from(PathBuf::from(
+            "ethereum-tests/GeneralStateTests/stRandom/randomStatetest0.json",
+        ));
+
+        // When
+        let components = path.components_after(BLOCKCHAIN_TESTS);
+
+        // Then
+        assert!(components.is_none());
+    }
+
+    #[test]
+    fn test_read_case_names() {
+        // Given
+        let path = PathWrapper::from(PathBuf::from(
+            "src/test_data/BlockchainTests/GeneralStateTests/stCallCreateCallCodeTest/Call1024PreCalls.json",
+        ));
+
+        // When
+        let case_names = path.read_case_names().unwrap();
+
+        // Then
+        assert_eq!(
+            case_names,
+            vec![
+                "Call1024PreCalls_d0g0v0_Shanghai",
+                "Call1024PreCalls_d0g1v0_Shanghai"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_forks() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "blob_tx_d0g0v0_Shanghai": {},
+                "blob_tx_d1g0v0_Shanghai": {},
+                "blob_tx_d0g0v0_Cancun": {},
+                "blob_tx": {}
+            }"#,
+        )
+        .unwrap();
+
+        // When
+        let forks = PathWrapper::from(path).forks().unwrap();
+
+        // Then
+        assert_eq!(forks, BTreeSet::from([Fork::Shanghai, Fork::Cancun]));
+    }
+
+    #[test]
+    fn test_read_case_names_not_an_object() {
+        // Given
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.json");
+        std::fs::write(&path, "[1, 2, 3]").unwrap();
+
+        // When
+        let err = PathWrapper::from(path.clone())
+            .read_case_names()
+            .unwrap_err();
+
+        // Then
+        assert!(err.to_string().contains("expected a JSON object"));
+        assert!(err.to_string().contains(&*path.to_string_lossy()));
+    }
 }
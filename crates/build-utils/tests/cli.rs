@@ -0,0 +1,83 @@
+use std::{fs, process::Command};
+
+#[test]
+fn test_check_warn_entry() {
+    // Given
+    let dir = tempfile::tempdir().unwrap();
+    let filter = dir.path().join("filter.yml");
+    fs::write(
+        &filter,
+        "filename:\n  stRandom:\n    - randomStatetest0\n    - pattern: randomStatetest1\n      severity: warn\nregex: {}\ntestname: {}",
+    )
+    .unwrap();
+    let check = |path: &str| {
+        Command::new(env!("CARGO_BIN_EXE_build-utils"))
+            .arg("--filter")
+            .arg(&filter)
+            .arg("check")
+            .arg(path)
+            .output()
+            .unwrap()
+    };
+
+    // When
+    let warn_only = check("GeneralStateTests/stRandom/randomStatetest1.json");
+    let skipped = check("GeneralStateTests/stRandom/randomStatetest0.json");
+
+    // Then
+    assert!(warn_only.status.success());
+    assert!(String::from_utf8_lossy(&warn_only.stdout)
+        .starts_with("runs, failures reported as warnings"));
+    assert!(!skipped.status.success());
+    assert!(String::from_utf8_lossy(&skipped.stdout).starts_with("skipped"));
+}
+
+#[test]
+fn test_diff_json() {
+    // Given
+    let dir = tempfile::tempdir().unwrap();
+    let old = dir.path().join("old.yml");
+    let new = dir.path().join("new.yml");
+    fs::write(
+        &old,
+        "filename:\n  stRandom: [randomStatetest0, randomStatetest1]\nregex: {}\ntestname: {}",
+    )
+    .unwrap();
+    fs::write(
+        &new,
+        "filename:\n  stRandom: [randomStatetest1, randomStatetest2]\nregex:\n  stBadOpcode: [opc.*]\ntestname: {}",
+    )
+    .unwrap();
+
+    // When
+    let output = Command::new(env!("CARGO_BIN_EXE_build-utils"))
+        .arg("--filter")
+        .arg(&old)
+        .arg("diff")
+        .arg(&new)
+        .arg("--json")
+        .output()
+        .unwrap();
+
+    // Then
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["report"]["added"],
+        serde_json::json!([["stRandom", "randomStatetest2"], ["stBadOpcode", "opc.*"]])
+    );
+    assert_eq!(
+        json["report"]["removed"],
+        serde_json::json!([["stRandom", "randomStatetest0"]])
+    );
+    assert_eq!(json["summary"]["added"], 2);
+    assert_eq!(json["summary"]["removed"], 1);
+    assert_eq!(
+        json["summary"]["per_category"]["filename"],
+        serde_json::json!([1, 1])
+    );
+    assert_eq!(
+        json["summary"]["per_category"]["regex"],
+        serde_json::json!([1, 0])
+    );
+}
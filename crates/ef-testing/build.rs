@@ -4,7 +4,11 @@ use std::{
     sync::Arc,
 };
 
-use build_utils::{converter::EfTests, dir_reader::DirReader, filter::Filter};
+use build_utils::{
+    converter::EfTests,
+    dir_reader::DirReader,
+    filter::{Filter, SKIP_FILE_ENV},
+};
 use lazy_static::lazy_static;
 
 const SKIPPED_TESTS: &str = "../../blockchain-tests-skip.yml";
@@ -19,12 +23,16 @@ lazy_static! {
 
 fn main() {
     // Tell Cargo that if the given file changes -> to rerun this build script.
-    cargo_emit::rerun_if_changed!(SKIPPED_TESTS);
-    let filter = Filter::load_file(SKIPPED_TESTS).expect("Unable to load test filter file");
+    cargo_emit::rerun_if_env_changed!(SKIP_FILE_ENV);
+    cargo_emit::rerun_if_changed!(std::env::var(SKIP_FILE_ENV)
+        .as_deref()
+        .unwrap_or(SKIPPED_TESTS));
+    let filter = Filter::load_from_env(SKIPPED_TESTS).expect("Unable to load test filter file");
     let maybe_cached_filter = Filter::load_file(CACHED_SKIPPED_TESTS).ok();
 
-    // Get the diff between the cached filter and the current one
-    let mut target = maybe_cached_filter.map(|cached_filter| filter.diff(&cached_filter));
+    // Get the diff between the cached filter and the current one, if it doesn't
+    // affect every folder
+    let mut target = maybe_cached_filter.and_then(|cached_filter| filter.diff(&cached_filter));
 
     // Check that we already have tests in the folder
     let mut current_tests = DirReader::walk_dir((INTEGRATION_TESTS_PATH.clone()).into());